@@ -3,6 +3,7 @@ use esp_idf_hal::delay::FreeRtos;
 use esp_idf_hal::gpio::{Output, OutputPin, PinDriver};
 use profont::{PROFONT_12_POINT, PROFONT_24_POINT};
 
+use shared::sound::Sound;
 use shared::tiny_display::TinyDisplay;
 
 #[derive(Debug, PartialEq)]
@@ -70,11 +71,12 @@ pub struct Game<'a, LED: OutputPin> {
     state: GameState,
     led: PinDriver<'a, LED, Output>,
     display: TinyDisplay<'a>,
+    sound: Sound,
 }
 
 impl<'a, LED: OutputPin> Game<'a, LED> {
-    pub fn new(display: TinyDisplay<'a>, led: PinDriver<'a, LED, Output>) -> anyhow::Result<Game<'a, LED>> {
-        let mut game = Self { display, led, state: GameState::new() };
+    pub fn new(display: TinyDisplay<'a>, led: PinDriver<'a, LED, Output>, sound: Sound) -> anyhow::Result<Game<'a, LED>> {
+        let mut game = Self { display, led, sound, state: GameState::new() };
 
         game.initialize()?;
 
@@ -85,7 +87,7 @@ impl<'a, LED: OutputPin> Game<'a, LED> {
         match self.state.update(code, &mut self.display)? {
             State::Win => self.draw_win_state(),
             State::Lose => self.draw_lose_state(),
-            State::Playing => Ok(())
+            State::Playing => self.sound.beep(1500, 30),
         }
     }
 
@@ -106,6 +108,7 @@ impl<'a, LED: OutputPin> Game<'a, LED> {
         self.display.draw_text(&"Congratulations".to_string(), PROFONT_12_POINT, 5, 35)?;
         self.display.flush()?;
         self.led.set_high()?;
+        self.sound.success()?;
         self.reset(1000)
     }
 
@@ -113,6 +116,7 @@ impl<'a, LED: OutputPin> Game<'a, LED> {
         self.display.clear();
         self.display.draw_text(&"Try Again!".to_string(), PROFONT_12_POINT, 25, 35)?;
         self.display.flush()?;
+        self.sound.error()?;
         self.reset(500)
     }
 
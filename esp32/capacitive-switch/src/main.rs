@@ -3,6 +3,7 @@ use anyhow::anyhow;
 use esp_idf_hal::delay::FreeRtos;
 use esp_idf_hal::gpio::PinDriver;
 use esp_idf_hal::prelude::*;
+use shared::sound::Sound;
 use shared::tiny_display::TinyDisplay;
 use crate::capacitive_sensor::CapacitiveSensor;
 use crate::game::Game;
@@ -22,6 +23,9 @@ fn main() -> anyhow::Result<()> {
     // For Green LED
     let led = peripherals.pins.gpio18;
 
+    // For Passive Buzzer
+    let buzzer = peripherals.pins.gpio17;
+
     // For Capacitive Sensor
     let one = peripherals.pins.gpio1;
     let two = peripherals.pins.gpio2;
@@ -30,9 +34,10 @@ fn main() -> anyhow::Result<()> {
 
     let led = PinDriver::output(led)?;
     let display = TinyDisplay::new(peripherals.i2c0, sda, scl)?;
+    let sound = Sound::new(peripherals.rmt.channel0, buzzer)?;
     let mut sensor = CapacitiveSensor::new(one, two, three, four)?;
 
-    let game = Mutex::new(Game::new(display, led)?);
+    let game = Mutex::new(Game::new(display, led, sound)?);
 
     sensor.on_touch(Box::new(move |button| {
         game.lock()
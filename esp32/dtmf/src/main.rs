@@ -4,6 +4,7 @@ use esp_idf_hal::gpio::InputPin;
 use esp_idf_hal::prelude::Peripherals;
 use profont::PROFONT_24_POINT;
 
+use shared::sound::Sound;
 use shared::tiny_display::TinyDisplay;
 use crate::dtmf::DTMF;
 
@@ -25,12 +26,16 @@ fn main() -> anyhow::Result<()> {
     let scl = peripherals.pins.gpio7;
     let sda = peripherals.pins.gpio6;
 
+    // For Passive Buzzer
+    let buzzer = peripherals.pins.gpio5;
+
     let mut instance = DTMF::new(q1, q2, q3, q4, st)?;
 
     let mut display = TinyDisplay::new(peripherals.i2c0, sda, scl)?;
     display.clear();
 
     let display = Mutex::new(display);
+    let sound = Sound::new(peripherals.rmt.channel0, buzzer)?;
 
     instance.on_pressed(Box::new(move |number| {
         let mut display = display.lock().expect("failed to acquire lock");
@@ -38,6 +43,8 @@ fn main() -> anyhow::Result<()> {
         display.clear();
         display.draw_text(&number.to_string(), PROFONT_24_POINT, 55, 42).expect("failed to draw text");
         display.flush().expect("failed to flush display");
+
+        sound.chirp().expect("failed to play sound");
     }));
 
     instance.listen();
@@ -0,0 +1,110 @@
+use crate::lcd::{Backlight, LcdError};
+use crate::transport::{Register, Transport};
+
+// How the PCF8574 pins are wired to the LCD on the usual backpacks, only D4-D7 are connected
+pub const RS: u8 = 0b0000_0001;
+pub const RW: u8 = 0b0000_0010;
+// Data is latched when the enable line goes low
+pub const ENABLE: u8 = 0b0000_0100;
+pub const BACKLIGHT: u8 = 0b0000_1000;
+pub const DATA_PINS: u8 = 0b1111_0000;
+
+// Bytes sent in a single write to the expander, each character takes 4 of them
+const BATCH_SIZE: usize = 64;
+
+/// The 8 pins of the expander, written and read a whole byte at a time.
+pub trait Port {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), LcdError>;
+
+    fn read(&mut self) -> Result<u8, LcdError>;
+}
+
+/// An LCD driven in 4 bit mode through a PCF8574, the enable pulses are toggled by the bytes written to the expander.
+pub struct Backpack<PORT> {
+    port: PORT,
+    backlight: Backlight,
+}
+
+impl<PORT> Backpack<PORT> where PORT: Port {
+    pub fn new(port: PORT) -> Self {
+        Self {
+            port,
+            backlight: Backlight::On,
+        }
+    }
+
+    pub fn port(&self) -> &PORT {
+        &self.port
+    }
+
+    pub fn port_mut(&mut self) -> &mut PORT {
+        &mut self.port
+    }
+
+    // Bytes that pulse the enable line with the nibble on the data lines, it is held while enable goes low
+    fn pulse(&self, nibble: u8, register: Register) -> [u8; 2] {
+        let state = nibble | rs(register) | self.backlight as u8;
+        [state | ENABLE, state]
+    }
+}
+
+impl<PORT> Transport for Backpack<PORT> where PORT: Port {
+    fn is_eight_bit(&self) -> bool {
+        false
+    }
+
+    fn write_nibble(&mut self, nibble: u8) -> Result<(), LcdError> {
+        let bytes = self.pulse(nibble & DATA_PINS, Register::Instruction);
+        self.port.write(&bytes)
+    }
+
+    // Over I2C at 100kHz every byte on the bus takes about 90us, longer than any instruction but Clear and
+    // ReturnHome takes, so no waits are needed between them and the enable pulse is far wider than the 450ns
+    // the LCD requires. Sending them in as few writes as possible is what makes it fast.
+    fn write(&mut self, bytes: &[u8], register: Register) -> Result<(), LcdError> {
+        let mut buffer: Vec<u8> = Vec::with_capacity(bytes.len() * 4);
+
+        for byte in bytes {
+            let high_bits: u8 = byte & DATA_PINS;
+            let low_bits: u8 = (byte << 4) & DATA_PINS;
+
+            buffer.extend_from_slice(&self.pulse(high_bits, register));
+            buffer.extend_from_slice(&self.pulse(low_bits, register));
+        }
+
+        for chunk in buffer.chunks(BATCH_SIZE) {
+            self.port.write(chunk)?;
+        }
+
+        Ok(())
+    }
+
+    // The data pins have to be written high before the LCD can pull them low, then each nibble
+    // is read while the enable line is high
+    fn read(&mut self, register: Register) -> Result<u8, LcdError> {
+        let state = DATA_PINS | RW | rs(register) | self.backlight as u8;
+        let mut value = 0;
+
+        for shift in [0, 4] {
+            self.port.write(&[state | ENABLE])?;
+            let nibble = self.port.read()? & DATA_PINS;
+            self.port.write(&[state])?;
+
+            value |= nibble >> shift;
+        }
+
+        Ok(value)
+    }
+
+    fn set_backlight(&mut self, state: Backlight) -> Result<(), LcdError> {
+        self.backlight = state;
+        self.port.write(&[self.backlight as u8])
+    }
+}
+
+fn rs(register: Register) -> u8 {
+    match register {
+        Register::Instruction => 0,
+        Register::Data => RS,
+    }
+}
@@ -0,0 +1,137 @@
+use crate::glyphs::Glyph;
+
+/// Character ROM the HD44780 was made with, the part number ends in A00 or A02.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Rom {
+    // ASCII, Japanese katakana and a few Greek letters and symbols
+    A00,
+    // ASCII, Latin-1 and a few Cyrillic and Greek letters
+    A02,
+}
+
+/// Returns the code of the character in the ROM, if it has one.
+pub fn rom_code(rom: Rom, char: char) -> Option<u8> {
+    match rom {
+        Rom::A00 => a00_code(char),
+        Rom::A02 => a02_code(char),
+    }
+}
+
+fn a00_code(char: char) -> Option<u8> {
+    let code = match char {
+        // Backslash and tilde are replaced by the yen sign and an arrow
+        '\\' | '~' => return None,
+        ' '..='}' => char as u8,
+        '¥' => 0x5C,
+        '→' => 0x7E,
+        '←' => 0x7F,
+        '·' => 0xA5,
+        // Half-width katakana are laid out in the same order as JIS X 0201
+        '\u{FF61}'..='\u{FF9F}' => (char as u32 - 0xFF61 + 0xA1) as u8,
+        '°' => 0xDF,
+        'α' => 0xE0,
+        'ä' => 0xE1,
+        'ß' | 'β' => 0xE2,
+        'ε' => 0xE3,
+        'µ' | 'μ' => 0xE4,
+        'σ' => 0xE5,
+        'ρ' => 0xE6,
+        '√' => 0xE8,
+        '¢' => 0xEC,
+        'ñ' => 0xEE,
+        'ö' => 0xEF,
+        'θ' => 0xF2,
+        '∞' => 0xF3,
+        'Ω' => 0xF4,
+        'ü' => 0xF5,
+        'Σ' => 0xF6,
+        'π' => 0xF7,
+        '÷' => 0xFD,
+        '█' => 0xFF,
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+fn a02_code(char: char) -> Option<u8> {
+    let code = match char {
+        '▶' => 0x10,
+        '◀' => 0x11,
+        '↑' => 0x18,
+        '↓' => 0x19,
+        '→' => 0x1A,
+        '←' => 0x1B,
+        '≤' => 0x1C,
+        '≥' => 0x1D,
+        '▲' => 0x1E,
+        '▼' => 0x1F,
+        ' '..='~' => char as u8,
+        // The upper half follows Latin-1
+        '\u{A1}'..='\u{FF}' => char as u8,
+        _ => return None,
+    };
+
+    Some(code)
+}
+
+/// Returns the character shown for a ROM code, the opposite of [`rom_code`].
+pub fn rom_char(rom: Rom, code: u8) -> Option<char> {
+    // Every character either ROM has a code for lives in one of these ranges
+    let candidates = (' '..='~')
+        .chain('\u{A1}'..='\u{FF}')
+        .chain('\u{FF61}'..='\u{FF9F}')
+        .chain(['¥', '→', '←', '↑', '↓', '≤', '≥', '▲', '▼', '▶', '◀', 'β', 'ε', 'μ', 'σ', 'ρ', '√', 'θ', '∞', 'Ω', 'Σ', 'π', 'α', '█']);
+
+    candidates.into_iter().find(|char| rom_code(rom, *char) == Some(code))
+}
+
+/// Glyphs that can be stored in CGRAM for characters missing from both ROMs or only found in one of them.
+pub fn custom_glyph(char: char) -> Option<Glyph> {
+    let glyph = match char {
+        '\\' => [0b00000, 0b10000, 0b01000, 0b00100, 0b00010, 0b00001, 0b00000, 0b00000],
+        '~' => [0b00000, 0b00000, 0b01000, 0b10101, 0b00010, 0b00000, 0b00000, 0b00000],
+        'é' => [0b00010, 0b00100, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110, 0b00000],
+        'è' => [0b01000, 0b00100, 0b01110, 0b10001, 0b11111, 0b10000, 0b01110, 0b00000],
+        'à' => [0b01000, 0b00100, 0b01110, 0b00001, 0b01111, 0b10001, 0b01111, 0b00000],
+        'ç' => [0b00000, 0b00000, 0b01110, 0b10000, 0b10001, 0b01110, 0b00100, 0b01100],
+        '€' => [0b00110, 0b01001, 0b11100, 0b01000, 0b11100, 0b01001, 0b00110, 0b00000],
+        '♥' => [0b00000, 0b01010, 0b11111, 0b11111, 0b01110, 0b00100, 0b00000, 0b00000],
+        _ => return None,
+    };
+
+    Some(glyph)
+}
+
+/// Closest character found in every ROM, accents are dropped and anything unknown becomes `?`.
+pub fn fallback(char: char) -> char {
+    match char {
+        'à' | 'á' | 'â' | 'ã' | 'ä' | 'å' => 'a',
+        'À' | 'Á' | 'Â' | 'Ã' | 'Ä' | 'Å' => 'A',
+        'è' | 'é' | 'ê' | 'ë' => 'e',
+        'È' | 'É' | 'Ê' | 'Ë' => 'E',
+        'ì' | 'í' | 'î' | 'ï' => 'i',
+        'Ì' | 'Í' | 'Î' | 'Ï' => 'I',
+        'ò' | 'ó' | 'ô' | 'õ' | 'ö' | 'ø' => 'o',
+        'Ò' | 'Ó' | 'Ô' | 'Õ' | 'Ö' | 'Ø' => 'O',
+        'ù' | 'ú' | 'û' | 'ü' => 'u',
+        'Ù' | 'Ú' | 'Û' | 'Ü' => 'U',
+        'ç' => 'c',
+        'Ç' => 'C',
+        'ñ' => 'n',
+        'Ñ' => 'N',
+        'ý' | 'ÿ' => 'y',
+        'Ý' => 'Y',
+        '‘' | '’' | '´' | '`' => '\'',
+        '“' | '”' | '„' => '"',
+        '–' | '—' => '-',
+        '…' => '.',
+        '×' => 'x',
+        '↑' => '^',
+        '→' | '▶' => '>',
+        '←' | '◀' => '<',
+        '\\' => '/',
+        '~' => '-',
+        _ => '?',
+    }
+}
@@ -0,0 +1,369 @@
+//! Software model of an HD44780 behind a PCF8574 backpack, fed with the bytes written to the expander.
+//!
+//! It keeps the same state the LCD does (DDRAM, CGRAM, address counter, entry mode, display control,
+//! display shift and backlight) so what the driver sends can be checked on the host without hardware.
+
+use crate::backpack::{BACKLIGHT, DATA_PINS, ENABLE, Port, RS, RW};
+use crate::charset::{rom_char, Rom};
+use crate::lcd::{Geometry, LcdError};
+
+const DDRAM_SIZE: usize = 0x80;
+const CGRAM_SIZE: usize = 0x40;
+// Characters in each line when there are two, a single line holds twice as many
+const LINE_LENGTH: u8 = 40;
+const SECOND_LINE: u8 = 0x40;
+
+#[derive(Debug, Clone)]
+pub struct Emulator {
+    rom: Rom,
+    // Last byte written to the expander
+    port: u8,
+    // 8 bit mode at power up, switched to 4 bits with a function set
+    eight_bit: bool,
+    // High nibble waiting for the low one in 4 bit mode
+    pending: Option<u8>,
+    // Whether the high nibble of a read was already taken
+    reading_low: bool,
+    ddram: [u8; DDRAM_SIZE],
+    cgram: [u8; CGRAM_SIZE],
+    address: u8,
+    // Whether data goes to CGRAM, set by the last address command
+    cgram_selected: bool,
+    increment: bool,
+    shift_display: bool,
+    display_on: bool,
+    cursor_on: bool,
+    blink_on: bool,
+    two_lines: bool,
+    // How many characters the display is shifted to the left
+    display_shift: u8,
+    instructions: usize,
+}
+
+impl Emulator {
+    pub fn new(rom: Rom) -> Self {
+        Self {
+            rom,
+            port: 0,
+            eight_bit: true,
+            pending: None,
+            reading_low: false,
+            // DDRAM holds random values at power up, spaces are easier to read
+            ddram: [b' '; DDRAM_SIZE],
+            cgram: [0; CGRAM_SIZE],
+            address: 0,
+            cgram_selected: false,
+            increment: true,
+            shift_display: false,
+            display_on: false,
+            cursor_on: false,
+            blink_on: false,
+            two_lines: false,
+            display_shift: 0,
+            instructions: 0,
+        }
+    }
+
+    /// Handles a write to the expander, data is latched on every falling edge of the enable line.
+    pub fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            let falling = self.port & ENABLE != 0 && byte & ENABLE == 0;
+            let latched = self.port;
+
+            self.port = *byte;
+
+            if !falling {
+                continue;
+            }
+
+            match latched & RW {
+                0 => self.latch(latched),
+                _ => self.end_read(latched),
+            }
+        }
+    }
+
+    /// Handles a read from the expander. While reading with the enable line high the LCD drives the data pins
+    /// with the busy flag and address counter, or the data at the address counter, one nibble at a time.
+    pub fn read(&self) -> u8 {
+        let control = self.port & 0b0000_1111;
+
+        if control & RW == 0 || control & ENABLE == 0 {
+            // The data pins are pulled up by the expander when nothing drives them
+            return self.port | DATA_PINS;
+        }
+
+        // The busy flag is the top bit, the emulator is never busy
+        let value = match control & RS {
+            0 => self.address & 0b0111_1111,
+            _ => self.read_memory(),
+        };
+
+        let nibble = match self.reading_low {
+            false => value & DATA_PINS,
+            true => value << 4,
+        };
+
+        control | nibble
+    }
+
+    // A read is over once both nibbles were clocked out, reading data moves the address counter along
+    fn end_read(&mut self, port: u8) {
+        if self.reading_low && port & RS != 0 {
+            self.advance(1);
+        }
+
+        self.reading_low = !self.reading_low;
+    }
+
+    fn latch(&mut self, port: u8) {
+        let nibble = port & DATA_PINS;
+        let rs = port & RS != 0;
+
+        if self.eight_bit {
+            // Only the upper 4 data lines are wired, the lower ones read as 0
+            self.execute(nibble, rs);
+            return;
+        }
+
+        match self.pending.take() {
+            None => self.pending = Some(nibble),
+            Some(high) => self.execute(high | nibble >> 4, rs),
+        }
+    }
+
+    fn execute(&mut self, value: u8, rs: bool) {
+        self.instructions += 1;
+
+        if rs {
+            self.write_memory(value);
+            return;
+        }
+
+        match value {
+            0b1000_0000..=0xFF => {
+                self.address = value & 0b0111_1111;
+                self.cgram_selected = false;
+            }
+            0b0100_0000..=0b0111_1111 => {
+                self.address = value & 0b0011_1111;
+                self.cgram_selected = true;
+            }
+            0b0010_0000..=0b0011_1111 => {
+                self.eight_bit = value & 0b0001_0000 != 0;
+                self.two_lines = value & 0b0000_1000 != 0;
+            }
+            0b0001_0000..=0b0001_1111 => {
+                let right = value & 0b0000_0100 != 0;
+
+                match value & 0b0000_1000 != 0 {
+                    true => self.shift(right),
+                    false => self.move_address(right),
+                }
+            }
+            0b0000_1000..=0b0000_1111 => {
+                self.display_on = value & 0b0000_0100 != 0;
+                self.cursor_on = value & 0b0000_0010 != 0;
+                self.blink_on = value & 0b0000_0001 != 0;
+            }
+            0b0000_0100..=0b0000_0111 => {
+                self.increment = value & 0b0000_0010 != 0;
+                self.shift_display = value & 0b0000_0001 != 0;
+            }
+            0b0000_0010..=0b0000_0011 => {
+                self.address = 0;
+                self.cgram_selected = false;
+                self.display_shift = 0;
+            }
+            0b0000_0001 => {
+                self.ddram.fill(b' ');
+                self.address = 0;
+                self.cgram_selected = false;
+                self.increment = true;
+                self.display_shift = 0;
+            }
+            _ => {}
+        }
+    }
+
+    fn write_memory(&mut self, value: u8) {
+        match self.cgram_selected {
+            true => self.cgram[self.address as usize % CGRAM_SIZE] = value & 0b0001_1111,
+            false => self.ddram[self.address as usize % DDRAM_SIZE] = value,
+        }
+
+        self.advance(1);
+
+        if self.shift_display && !self.cgram_selected {
+            // The display follows the cursor, moving the opposite way so the cursor stays in place
+            self.shift(!self.increment);
+        }
+    }
+
+    fn read_memory(&self) -> u8 {
+        match self.cgram_selected {
+            true => self.cgram[self.address as usize % CGRAM_SIZE],
+            false => self.ddram[self.address as usize % DDRAM_SIZE],
+        }
+    }
+
+    fn advance(&mut self, count: u8) {
+        for _ in 0..count {
+            self.move_address(self.increment);
+        }
+    }
+
+    fn move_address(&mut self, forward: bool) {
+        if self.cgram_selected {
+            self.address = match forward {
+                true => (self.address + 1) % CGRAM_SIZE as u8,
+                false => (self.address + CGRAM_SIZE as u8 - 1) % CGRAM_SIZE as u8,
+            };
+
+            return;
+        }
+
+        self.address = match (self.two_lines, forward, self.address) {
+            (false, true, address) => (address + 1) % (LINE_LENGTH * 2),
+            (false, false, 0) => LINE_LENGTH * 2 - 1,
+            (false, false, address) => address - 1,
+            (true, true, 0x27) => SECOND_LINE,
+            (true, true, 0x67) => 0x00,
+            (true, true, address) => address + 1,
+            (true, false, 0x00) => 0x67,
+            (true, false, SECOND_LINE) => 0x27,
+            (true, false, address) => address - 1,
+        };
+    }
+
+    fn shift(&mut self, right: bool) {
+        let length = self.line_length();
+
+        // Shifting the display right moves the characters right, so the window moves left
+        self.display_shift = match right {
+            true => (self.display_shift + length - 1) % length,
+            false => (self.display_shift + 1) % length,
+        };
+    }
+
+    fn line_length(&self) -> u8 {
+        match self.two_lines {
+            true => LINE_LENGTH,
+            false => LINE_LENGTH * 2,
+        }
+    }
+
+    /// Returns the characters visible on a display of the given size, or blank rows when the display is off.
+    /// Custom characters are shown as ① to ⑧ and codes missing from the ROM as `?`.
+    pub fn render(&self, columns: u8, rows: u8) -> Vec<String> {
+        (0..rows)
+            .map(|row| {
+                (0..columns)
+                    .map(|column| match self.display_on {
+                        true => self.character(row, column, columns),
+                        false => ' ',
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn character(&self, row: u8, column: u8, columns: u8) -> char {
+        // 4 row displays continue the 2 lines on the rows below them
+        let (line, column) = match row {
+            0 | 1 => (row, column),
+            _ => (row - 2, column + columns),
+        };
+
+        let start = match line {
+            0 => 0,
+            _ => SECOND_LINE,
+        };
+
+        let offset = (column + self.display_shift) % self.line_length();
+        let code = self.ddram[(start + offset) as usize];
+
+        match code {
+            // Codes 8 to 15 show the same custom characters as 0 to 7
+            0x00..=0x0F => char::from_u32('①' as u32 + (code & 0b0111) as u32).unwrap_or('?'),
+            code => rom_char(self.rom, code).unwrap_or('?'),
+        }
+    }
+
+    /// Row and column the cursor is on for a display of the given size,
+    /// None while it points at CGRAM or at an address the display doesn't show.
+    pub fn cursor(&self, geometry: Geometry) -> Option<(u8, u8)> {
+        if self.cgram_selected {
+            return None;
+        }
+
+        geometry
+            .row_offsets()
+            .iter()
+            .enumerate()
+            .find(|(_, offset)| (**offset..*offset + geometry.columns()).contains(&self.address))
+            .map(|(row, offset)| (row as u8, self.address - offset))
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    pub fn ddram(&self) -> &[u8] {
+        &self.ddram
+    }
+
+    /// The 8 rows of the glyph stored in the slot.
+    pub fn glyph(&self, slot: u8) -> [u8; 8] {
+        let start = (slot as usize % 8) * 8;
+        let mut glyph = [0; 8];
+        glyph.copy_from_slice(&self.cgram[start..start + 8]);
+        glyph
+    }
+
+    pub fn is_backlight_on(&self) -> bool {
+        self.port & BACKLIGHT != 0
+    }
+
+    pub fn is_display_on(&self) -> bool {
+        self.display_on
+    }
+
+    pub fn is_cursor_on(&self) -> bool {
+        self.cursor_on
+    }
+
+    pub fn is_blink_on(&self) -> bool {
+        self.blink_on
+    }
+
+    pub fn is_four_bit(&self) -> bool {
+        !self.eight_bit
+    }
+
+    pub fn is_two_lines(&self) -> bool {
+        self.two_lines
+    }
+
+    pub fn display_shift(&self) -> u8 {
+        self.display_shift
+    }
+
+    /// Number of instructions and data writes executed so far.
+    pub fn instructions(&self) -> usize {
+        self.instructions
+    }
+}
+
+// Lets `LCD` drive the emulator through a `Backpack`, so the whole driver runs on the host
+impl Port for Emulator {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), LcdError> {
+        Emulator::write(self, bytes);
+
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<u8, LcdError> {
+        Ok(Emulator::read(self))
+    }
+}
@@ -0,0 +1,130 @@
+use crate::glyphs::BLANK;
+use crate::lcd::{LCD, LcdError};
+use crate::transport::Transport;
+
+// Unchanged cells between two changed ones that are rewritten anyway, since skipping
+// them costs an address command which takes as long as writing a character
+const MAX_GAP: usize = 1;
+
+/// Keeps a copy of every visible character so the screen can be redrawn without clearing it.
+/// Text is written into the buffer and `flush` only sends the characters that changed.
+pub struct BufferedLCD<TRANSPORT, DELAY> {
+    lcd: LCD<TRANSPORT, DELAY>,
+    // Character codes of every cell, row after row
+    buffer: Vec<u8>,
+    // What the display is showing, None when unknown
+    shown: Vec<Option<u8>>,
+    row: u8,
+    column: u8,
+}
+
+impl<TRANSPORT, DELAY> BufferedLCD<TRANSPORT, DELAY> where TRANSPORT: Transport, DELAY: Fn(u32) -> () {
+    /// Takes over an initialized LCD, the first flush draws every cell.
+    pub fn new(lcd: LCD<TRANSPORT, DELAY>) -> Self {
+        let cells = lcd.geometry().rows() as usize * lcd.geometry().columns() as usize;
+
+        Self {
+            lcd,
+            buffer: vec![BLANK; cells],
+            shown: vec![None; cells],
+            row: 0,
+            column: 0,
+        }
+    }
+
+    pub fn lcd(&mut self) -> &mut LCD<TRANSPORT, DELAY> {
+        &mut self.lcd
+    }
+
+    pub fn cursor_move_to(&mut self, row: u8, column: u8) -> Result<(), LcdError> {
+        if row >= self.lcd.geometry().rows() || column >= self.lcd.geometry().columns() {
+            return Err(LcdError::OutOfBounds { row, column });
+        }
+
+        self.row = row;
+        self.column = column;
+
+        Ok(())
+    }
+
+    /// Writes the text into the buffer at the cursor, anything past the end of the row is dropped.
+    pub fn write_str(&mut self, message: &str) -> Result<(), LcdError> {
+        let columns = self.lcd.geometry().columns();
+
+        for char in message.chars() {
+            if self.column >= columns {
+                break;
+            }
+
+            let code = self.lcd.encode(char)?;
+            let index = self.index(self.row, self.column);
+
+            self.buffer[index] = code;
+            self.column += 1;
+        }
+
+        Ok(())
+    }
+
+    /// Blanks the buffer, the display only changes on the next flush.
+    pub fn clear(&mut self) {
+        self.buffer.fill(BLANK);
+        self.row = 0;
+        self.column = 0;
+    }
+
+    /// Forgets what the display is showing so the next flush redraws everything,
+    /// for when it was written to through [`BufferedLCD::lcd`].
+    pub fn invalidate(&mut self) {
+        self.shown.fill(None);
+    }
+
+    /// Sends the cells that changed since the last flush, one address command per run of changes.
+    pub fn flush(&mut self) -> Result<(), LcdError> {
+        let columns = self.lcd.geometry().columns() as usize;
+
+        for row in 0..self.lcd.geometry().rows() {
+            let start = self.index(row, 0);
+            let mut column = 0;
+
+            while column < columns {
+                if !self.is_changed(start + column) {
+                    column += 1;
+                    continue;
+                }
+
+                // Extend the run while the changes are close enough together
+                let first = column;
+                let mut last = column;
+
+                while let Some(next) = (last + 1..columns).take(MAX_GAP + 1).find(|next| self.is_changed(start + next)) {
+                    last = next;
+                }
+
+                self.lcd.write_at(row, first as u8, &self.buffer[start + first..=start + last])?;
+
+                for index in start + first..=start + last {
+                    self.shown[index] = Some(self.buffer[index]);
+                }
+
+                column = last + 1;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn is_changed(&self, index: usize) -> bool {
+        self.shown[index] != Some(self.buffer[index])
+    }
+
+    fn index(&self, row: u8, column: u8) -> usize {
+        row as usize * self.lcd.geometry().columns() as usize + column as usize
+    }
+}
+
+impl<TRANSPORT, DELAY> std::fmt::Write for BufferedLCD<TRANSPORT, DELAY> where TRANSPORT: Transport, DELAY: Fn(u32) -> () {
+    fn write_str(&mut self, text: &str) -> std::fmt::Result {
+        BufferedLCD::write_str(self, text).map_err(|_| std::fmt::Error)
+    }
+}
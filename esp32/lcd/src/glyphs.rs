@@ -0,0 +1,51 @@
+// Custom characters are 5 pixels wide and 8 tall, one byte per row with the lower 5 bits used
+pub type Glyph = [u8; 8];
+
+// Characters from the ROM that are shared by every variant
+pub const BLANK: u8 = 0x20;
+pub const FULL_BLOCK: u8 = 0xFF;
+
+// Blocks filled from the left, 1 to 4 columns wide, a full block comes from the ROM
+pub const BAR_GLYPHS: [Glyph; 4] = [
+    [0b10000; 8],
+    [0b11000; 8],
+    [0b11100; 8],
+    [0b11110; 8],
+];
+
+// Pieces the big digits are assembled from
+pub const BIG_DIGIT_GLYPHS: [Glyph; 8] = [
+    // Upper left corner
+    [0b00111, 0b01111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111],
+    // Upper bar
+    [0b11111, 0b11111, 0b11111, 0b00000, 0b00000, 0b00000, 0b00000, 0b00000],
+    // Upper right corner
+    [0b11100, 0b11110, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111],
+    // Lower left corner
+    [0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b01111, 0b00111],
+    // Lower bar
+    [0b00000, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111, 0b11111, 0b11111],
+    // Lower right corner
+    [0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11111, 0b11110, 0b11100],
+    // Upper and middle bars
+    [0b11111, 0b11111, 0b11111, 0b00000, 0b00000, 0b00000, 0b11111, 0b11111],
+    // Middle and lower bars
+    [0b11111, 0b00000, 0b00000, 0b00000, 0b00000, 0b11111, 0b11111, 0b11111],
+];
+
+const B: u8 = BLANK;
+const F: u8 = FULL_BLOCK;
+
+// Top and bottom row of each digit from 0 to 9, numbers refer to the slots of the glyphs above
+pub const BIG_DIGITS: [[[u8; 3]; 2]; 10] = [
+    [[0, 1, 2], [3, 4, 5]],
+    [[1, 2, B], [4, F, 4]],
+    [[6, 6, 2], [3, 4, 4]],
+    [[6, 6, 2], [4, 4, 5]],
+    [[3, 4, F], [B, B, F]],
+    [[F, 6, 6], [4, 4, 5]],
+    [[0, 6, 6], [3, 4, 5]],
+    [[1, 1, 2], [B, B, F]],
+    [[0, 6, 2], [3, 4, 5]],
+    [[0, 6, 2], [B, B, F]],
+];
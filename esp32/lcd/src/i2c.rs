@@ -0,0 +1,110 @@
+use std::ops::RangeInclusive;
+
+use esp_idf_hal::delay::BLOCK;
+use esp_idf_hal::gpio::{InputPin, OutputPin};
+use esp_idf_hal::i2c::{I2c, I2cDriver};
+use esp_idf_hal::i2c::config::Config;
+use esp_idf_hal::peripheral::Peripheral;
+use esp_idf_hal::prelude::FromValueType;
+
+use crate::backpack::{Backpack, Port};
+use crate::lcd::{Backlight, Geometry, LCD, LcdError};
+
+// Address of the PCF8574T backpacks with no jumpers soldered
+pub const DEFAULT_ADDRESS: u8 = 0x27;
+// The PCF8574 answers from 0x20 to 0x27 and the PCF8574A from 0x38 to 0x3F, depending on the A0-A2 jumpers
+const PROBED_ADDRESSES: [RangeInclusive<u8>; 2] = [0x20..=0x27, 0x38..=0x3F];
+
+/// The PCF8574 on the I2C bus.
+pub struct I2cPort<'d> {
+    address: u8,
+    driver: I2cDriver<'d>,
+}
+
+impl<'d> I2cPort<'d> {
+    pub fn new(
+        i2c: impl Peripheral<P=impl I2c> + 'd,
+        sda: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
+        scl: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
+        address: u8,
+    ) -> Result<Self, LcdError> {
+        let driver = I2cDriver::new(i2c, sda, scl, &Config::new().baudrate(100.kHz().into()))?;
+
+        Ok(Self { address, driver })
+    }
+
+    /// Looks for a backpack on every address a PCF8574 or PCF8574A can have and uses the first one that answers.
+    pub fn probe(
+        i2c: impl Peripheral<P=impl I2c> + 'd,
+        sda: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
+        scl: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
+    ) -> Result<Self, LcdError> {
+        let mut driver = I2cDriver::new(i2c, sda, scl, &Config::new().baudrate(100.kHz().into()))?;
+
+        // Writing a byte with every pin low is harmless, a missing device doesn't acknowledge it
+        let address = PROBED_ADDRESSES
+            .into_iter()
+            .flatten()
+            .find(|address| driver.write(*address, &[Backlight::Off as u8], BLOCK).is_ok())
+            .ok_or(LcdError::NotFound)?;
+
+        Ok(Self { address, driver })
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+}
+
+impl<'d> Port for I2cPort<'d> {
+    fn write(&mut self, bytes: &[u8]) -> Result<(), LcdError> {
+        self.driver.write(self.address, bytes, BLOCK)?;
+
+        Ok(())
+    }
+
+    fn read(&mut self) -> Result<u8, LcdError> {
+        let mut buffer = [0];
+        self.driver.read(self.address, &mut buffer, BLOCK)?;
+
+        Ok(buffer[0])
+    }
+}
+
+pub type I2cTransport<'d> = Backpack<I2cPort<'d>>;
+
+impl<'d, DELAY> LCD<I2cTransport<'d>, DELAY> where DELAY: Fn(u32) -> () {
+    /// The delay function waits for the given amount of microseconds, such as `Ets::delay_us`.
+    pub fn new(
+        i2c: impl Peripheral<P=impl I2c> + 'd,
+        sda: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
+        scl: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
+        address: u8,
+        geometry: Geometry,
+        delay: DELAY,
+    ) -> Result<Self, LcdError> {
+        let port = I2cPort::new(i2c, sda, scl, address)?;
+
+        Ok(Self::with_transport(Backpack::new(port), geometry, delay))
+    }
+
+    /// Looks for a backpack on every address a PCF8574 or PCF8574A can have and initializes the first one that answers.
+    pub fn probe(
+        i2c: impl Peripheral<P=impl I2c> + 'd,
+        sda: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
+        scl: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
+        geometry: Geometry,
+        delay: DELAY,
+    ) -> Result<Self, LcdError> {
+        let port = I2cPort::probe(i2c, sda, scl)?;
+
+        let mut display = Self::with_transport(Backpack::new(port), geometry, delay);
+        display.initialize()?;
+
+        Ok(display)
+    }
+
+    pub fn address(&self) -> u8 {
+        self.transport().port().address()
+    }
+}
@@ -1,11 +1,56 @@
-use esp_idf_hal::delay::{BLOCK};
-use esp_idf_hal::gpio::{InputPin, OutputPin};
-use esp_idf_hal::i2c::{I2c, I2cDriver};
-use esp_idf_hal::i2c::config::Config;
-use esp_idf_hal::peripheral::Peripheral;
-use esp_idf_hal::prelude::FromValueType;
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "esp")]
 use esp_idf_sys::EspError;
 
+use crate::charset;
+use crate::charset::Rom;
+use crate::glyphs::{BAR_GLYPHS, BIG_DIGIT_GLYPHS, BIG_DIGITS, BLANK, FULL_BLOCK, Glyph};
+use crate::transport::{Register, Transport};
+
+// Number of custom characters that fit in CGRAM
+pub const CUSTOM_CHARACTERS: u8 = 8;
+// Columns of pixels in a character
+const CHARACTER_WIDTH: u32 = 5;
+// Columns taken by a big digit, including the space after it
+const BIG_DIGIT_WIDTH: u8 = 4;
+
+// The busy flag is the top bit of the byte read from the instruction register, the address counter the rest
+const BUSY_FLAG: u8 = 0b1000_0000;
+// Each poll takes a few I2C transactions or GPIO toggles, so this is far longer than any instruction
+const MAX_BUSY_POLLS: u32 = 100;
+// Most instructions take 37us, Clear and ReturnHome take 1.52ms
+pub(crate) const COMMAND_DELAY_US: u32 = 40;
+const HOME_DELAY_US: u32 = 1600;
+
+#[derive(Debug)]
+pub enum LcdError {
+    #[cfg(feature = "esp")]
+    Esp(EspError),
+    InvalidSlot(u8),
+    InvalidDigit(char),
+    OutOfBounds { row: u8, column: u8 },
+    // No backpack answered on any of the probed addresses
+    NotFound,
+    // The busy flag was still set after polling it MAX_BUSY_POLLS times
+    Busy,
+}
+
+impl std::error::Error for LcdError {}
+
+impl Display for LcdError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Error: {:?}", self)
+    }
+}
+
+#[cfg(feature = "esp")]
+impl From<EspError> for LcdError {
+    fn from(error: EspError) -> Self {
+        LcdError::Esp(error)
+    }
+}
+
 pub enum DisplayControl {
     Off = 0b0000_0000,
     // Defined as B on the datasheet
@@ -16,6 +61,7 @@ pub enum DisplayControl {
     DisplayOn = 0b0000_0100,
 }
 
+// Values are the PCF8574 pin the backlight is wired to
 #[derive(Copy, Clone)]
 pub enum Backlight {
     Off = 0x00,
@@ -24,8 +70,7 @@ pub enum Backlight {
 
 #[derive(Copy, Clone)]
 enum Mode {
-    Command = 0b0000_0000,
-    Data = 0b0000_0001,
+    EntryModeSet = 0b0000_0100,
     DisplayControl = 0b0000_1000,
     FunctionSet = 0b0010_0000,
 }
@@ -34,6 +79,61 @@ enum Command {
     Clear = 0b0000_0001,
     ReturnHome = 0b0000_0010,
     ShiftCursor = 0b0001_0000,
+    // The lower 6 bits hold the address, each custom character takes 8 bytes
+    SetCgramAddress = 0b0100_0000,
+    // The lower 7 bits hold the address
+    SetDdramAddress = 0b1000_0000,
+}
+
+/// Size of the display in characters, which also decides where each row starts in DDRAM.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Geometry {
+    // 16x1 modules with one controller line running across the whole display, from 0x00 to 0x0F
+    Columns16Rows1,
+    // 16x1 modules wired as two 8 character lines side by side, 0x00-0x07 on the left and 0x40-0x47
+    // on the right. They are addressed as 8x2, row 1 being the right half.
+    Columns16Rows1Split,
+    Columns16Rows2,
+    Columns20Rows2,
+    Columns20Rows4,
+    Columns40Rows2,
+}
+
+impl Geometry {
+    pub fn columns(&self) -> u8 {
+        match self {
+            Geometry::Columns16Rows1Split => 8,
+            Geometry::Columns16Rows1 | Geometry::Columns16Rows2 => 16,
+            Geometry::Columns20Rows2 | Geometry::Columns20Rows4 => 20,
+            Geometry::Columns40Rows2 => 40,
+        }
+    }
+
+    pub fn rows(&self) -> u8 {
+        self.row_offsets().len() as u8
+    }
+
+    // DDRAM address of the first character of each row, 4 row displays continue rows 0 and 1 on rows 2 and 3
+    pub(crate) fn row_offsets(&self) -> &'static [u8] {
+        match self {
+            Geometry::Columns16Rows1 => &[0x00],
+            Geometry::Columns16Rows1Split
+            | Geometry::Columns16Rows2
+            | Geometry::Columns20Rows2
+            | Geometry::Columns40Rows2 => &[0x00, 0x40],
+            Geometry::Columns20Rows4 => &[0x00, 0x40, 0x14, 0x54],
+        }
+    }
+}
+
+// Which glyphs are currently stored in CGRAM
+#[derive(Copy, Clone, PartialEq)]
+enum GlyphSet {
+    Custom,
+    Bar,
+    BigDigits,
+    // Characters missing from the ROM, allocated as they are written
+    Charset,
 }
 
 #[derive(Copy, Clone)]
@@ -46,6 +146,20 @@ enum Push {
     Push = 0b0000_1000,
 }
 
+enum EntryMode {
+    // Defined as I/D on the datasheet, the address counter goes down when it is not set
+    Increment = 0b0000_0010,
+    // Defined as S on the datasheet, the display shifts with every character written
+    Shift = 0b0000_0001,
+}
+
+/// Which way the cursor moves after each character is written.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum TextDirection {
+    LeftToRight,
+    RightToLeft,
+}
+
 enum BitMode {
     Bit4 = 0b0000_0000,
     Bit8 = 0b0001_0000,
@@ -56,79 +170,299 @@ enum Rows {
     TwoLines = 0b0000_1000,
 }
 
-enum Font {
+/// Character size, 5x11 only exists on single line displays, two line ones ignore it.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Font {
     FiveByEleven = 0b0000_0100,
     FiveByEight = 0b0000_0000,
 }
 
-pub struct LCD<'d, DELAY> {
-    address: u8,
+pub struct LCD<TRANSPORT, DELAY> {
+    geometry: Geometry,
     delay: DELAY,
-    backlight: Backlight,
     show_cursor: bool,
     blink_cursor: bool,
-    driver: I2cDriver<'d>,
-}
-
-impl<'d, DELAY> LCD<'d, DELAY> where DELAY: Fn(u32) -> () {
-    pub fn new(
-        i2c: impl Peripheral<P=impl I2c> + 'd,
-        sda: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
-        scl: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
-        delay: DELAY,
-    ) -> Result<Self, EspError> {
-        Ok(
-            Self {
-                delay,
-                show_cursor: false,
-                blink_cursor: false,
-                address: 0b0100_111,
-                backlight: Backlight::On,
-                driver: I2cDriver::new(i2c, sda, scl, &Config::new().baudrate(100.kHz().into()))?,
-            }
-        )
+    text_direction: TextDirection,
+    autoscroll: bool,
+    font: Font,
+    // Wait for the busy flag to clear instead of sleeping for the datasheet times
+    busy_polling: bool,
+    glyph_set: GlyphSet,
+    // Characters stored in each CGRAM slot while the glyph set is Charset
+    charset_glyphs: Vec<char>,
+    rom: Rom,
+    // Mirror of the DDRAM address counter, so the cursor can be put back after writing to CGRAM
+    cursor_address: u8,
+    transport: TRANSPORT,
+}
+
+impl<TRANSPORT, DELAY> LCD<TRANSPORT, DELAY> where TRANSPORT: Transport, DELAY: Fn(u32) -> () {
+    /// Drives an LCD over any transport, call `initialize` before using it.
+    /// The delay function waits for the given amount of microseconds, such as `Ets::delay_us`.
+    pub fn with_transport(transport: TRANSPORT, geometry: Geometry, delay: DELAY) -> Self {
+        Self {
+            delay,
+            geometry,
+            show_cursor: false,
+            blink_cursor: false,
+            text_direction: TextDirection::LeftToRight,
+            autoscroll: false,
+            font: Font::FiveByEight,
+            busy_polling: false,
+            glyph_set: GlyphSet::Custom,
+            charset_glyphs: vec![],
+            rom: Rom::A00,
+            cursor_address: 0,
+            transport,
+        }
+    }
+
+    pub fn transport(&self) -> &TRANSPORT {
+        &self.transport
     }
 
-    pub fn backlight(&mut self, state: Backlight) -> Result<(), EspError> {
-        self.backlight = state;
-        self.write_command(0, Mode::DisplayControl)?;
+    pub fn transport_mut(&mut self) -> &mut TRANSPORT {
+        &mut self.transport
+    }
+
+    pub fn backlight(&mut self, state: Backlight) -> Result<(), LcdError> {
+        self.transport.set_backlight(state)
+    }
+
+    /// Takes effect on the next `initialize`, custom characters are always 5x8.
+    pub fn set_font(&mut self, font: Font) {
+        self.font = font;
+    }
+
+    /// Sets which character ROM the display has, A00 unless told otherwise.
+    pub fn set_rom(&mut self, rom: Rom) {
+        self.rom = rom;
+    }
+
+    /// Writes the text at the cursor position. Characters missing from the ROM are stored in CGRAM when a glyph
+    /// is available for them and there is a free slot, otherwise they are replaced by the closest one, or `?`.
+    pub fn write_str(&mut self, message: &str) -> Result<(), LcdError> {
+        let mut characters: Vec<u8> = vec![];
+
+        for char in message.chars() {
+            characters.push(self.encode(char)?);
+        }
+
+        self.write_data(&characters)?;
 
         Ok(())
     }
 
-    pub fn write_str(&mut self, message: &str) -> Result<(), EspError> {
-        for char in message.chars().map(|char| char as u8) {
-            self.write_command(char, Mode::Data)?;
+    /// Returns the code to write for the character, storing a glyph in CGRAM if needed.
+    pub(crate) fn encode(&mut self, char: char) -> Result<u8, LcdError> {
+        if let Some(code) = charset::rom_code(self.rom, char) {
+            return Ok(code);
+        }
+
+        if let Some(slot) = self.allocate_glyph(char)? {
+            return Ok(slot);
+        }
+
+        Ok(charset::rom_code(self.rom, charset::fallback(char)).unwrap_or(b'?'))
+    }
+
+    // Finds or stores the glyph of the character in CGRAM, replacing any other glyph set.
+    // Characters already on the display that use a replaced slot change along with it.
+    fn allocate_glyph(&mut self, char: char) -> Result<Option<u8>, LcdError> {
+        let glyph = match charset::custom_glyph(char) {
+            Some(glyph) => glyph,
+            None => return Ok(None),
+        };
+
+        if self.glyph_set != GlyphSet::Charset {
+            self.glyph_set = GlyphSet::Charset;
+            self.charset_glyphs.clear();
         }
 
+        if let Some(slot) = self.charset_glyphs.iter().position(|stored| *stored == char) {
+            return Ok(Some(slot as u8));
+        }
+
+        if self.charset_glyphs.len() >= CUSTOM_CHARACTERS as usize {
+            return Ok(None);
+        }
+
+        let slot = self.charset_glyphs.len() as u8;
+
+        self.store_glyph(slot, &glyph)?;
+        self.charset_glyphs.push(char);
+
+        Ok(Some(slot))
+    }
+
+    pub fn scroll(&mut self, direction: Direction) -> Result<(), LcdError> {
+        self.write_command(Command::ShiftCursor as u8 | Push::Push as u8 | direction as u8)?;
+
+        Ok(())
+    }
+
+    /// Moves the cursor one character without touching DDRAM or shifting the display.
+    pub fn shift_cursor(&mut self, direction: Direction) -> Result<(), LcdError> {
+        self.write_command(Command::ShiftCursor as u8 | direction as u8)?;
+
+        let forward = match direction {
+            Direction::Left => false,
+            Direction::Right => true,
+        };
+
+        self.cursor_address = self.next_address(self.cursor_address, forward);
+
+        Ok(())
+    }
+
+    /// With RightToLeft every character is written to the left of the previous one, so text starting
+    /// at the last column ends up right aligned and reversed. `BufferedLCD` and `Marquee` expect LeftToRight.
+    pub fn text_direction(&mut self, direction: TextDirection) -> Result<(), LcdError> {
+        self.text_direction = direction;
+        self.write_entry_mode()?;
+
+        Ok(())
+    }
+
+    /// When on, the display shifts with every character written so the cursor stays in place
+    /// and the text slides in from the side it is written towards.
+    pub fn autoscroll(&mut self, state: bool) -> Result<(), LcdError> {
+        self.autoscroll = state;
+        self.write_entry_mode()?;
+
         Ok(())
     }
 
-    pub fn scroll(&mut self, direction: Direction) -> Result<(), EspError> {
-        self.write_command(Command::ShiftCursor as u8 | Push::Push as u8 | direction as u8, Mode::Command)?;
+    fn write_entry_mode(&mut self) -> Result<(), LcdError> {
+        let mut data = 0;
+
+        if self.text_direction == TextDirection::LeftToRight {
+            data |= EntryMode::Increment as u8
+        }
+
+        if self.autoscroll {
+            data |= EntryMode::Shift as u8
+        }
+
+        self.write_command(Mode::EntryModeSet as u8 | data)?;
 
         Ok(())
     }
 
-    pub fn initialize(&mut self) -> Result<(), EspError> {
-        // Init with 8 bit mode
+    /// When on, every instruction waits for the busy flag to clear instead of sleeping for the time
+    /// the datasheet gives, which needs the R/W line wired (it is on the usual backpacks).
+    pub fn busy_polling(&mut self, state: bool) {
+        self.busy_polling = state;
+    }
+
+    pub fn is_busy(&mut self) -> Result<bool, LcdError> {
+        Ok(self.transport.read(Register::Instruction)? & BUSY_FLAG != 0)
+    }
+
+    /// Reads the address counter, the DDRAM address of the cursor unless CGRAM was written last.
+    pub fn read_address(&mut self) -> Result<u8, LcdError> {
+        Ok(self.transport.read(Register::Instruction)? & !BUSY_FLAG)
+    }
+
+    /// Reads the codes of `length` characters starting at the row and column, the cursor is left where it was.
+    /// Fails when the characters don't all fit in the row.
+    pub fn read_ddram(&mut self, row: u8, column: u8, length: u8) -> Result<Vec<u8>, LcdError> {
+        let end = match column.checked_add(length) {
+            Some(end) if row < self.geometry.rows() && end <= self.geometry.columns() => end,
+            _ => return Err(LcdError::OutOfBounds { row, column: column.saturating_add(length) }),
+        };
+
+        if length == 0 {
+            return Ok(vec![]);
+        }
+
+        let cursor_address = self.cursor_address;
+        let mut characters = Vec::with_capacity(length as usize);
+
+        // Reading moves the address counter the same way writing does, so right to left starts from the end
+        match self.text_direction {
+            TextDirection::LeftToRight => self.cursor_move_to(row, column)?,
+            TextDirection::RightToLeft => self.cursor_move_to(row, end - 1)?,
+        }
+
+        for _ in 0..length {
+            characters.push(self.transport.read(Register::Data)?);
+        }
+
+        if self.text_direction == TextDirection::RightToLeft {
+            characters.reverse();
+        }
+
+        self.set_cursor_address(cursor_address)?;
+
+        Ok(characters)
+    }
+
+    /// Reads a whole row back as text, custom characters and codes missing from the ROM read as `?`.
+    pub fn read_row(&mut self, row: u8) -> Result<String, LcdError> {
+        let characters = self.read_ddram(row, 0, self.geometry.columns())?;
+
+        Ok(characters
+            .into_iter()
+            .map(|code| charset::rom_char(self.rom, code).unwrap_or('?'))
+            .collect())
+    }
+
+    // Waits until the LCD is ready for the next instruction, by sleeping or polling the busy flag
+    fn wait(&mut self, delay_us: u32) -> Result<(), LcdError> {
+        if !self.busy_polling {
+            (self.delay)(delay_us);
+            return Ok(());
+        }
+
+        for _ in 0..MAX_BUSY_POLLS {
+            if !self.is_busy()? {
+                return Ok(());
+            }
+        }
+
+        Err(LcdError::Busy)
+    }
+
+    pub fn initialize(&mut self) -> Result<(), LcdError> {
+        // Wait for the LCD to power up
+        (self.delay)(50_000);
+
+        // Init with 8 bit mode, sent 3 times with the waits from the datasheet
+        // so it works whichever mode the LCD was left in
         let mode_8bit: u8 = Mode::FunctionSet as u8 | BitMode::Bit8 as u8;
         self.write_4_bits(mode_8bit)?;
+        (self.delay)(4_100);
+        self.write_4_bits(mode_8bit)?;
+        (self.delay)(100);
         self.write_4_bits(mode_8bit)?;
 
-        // Switch to 4 bit mode
-        let mode_4bit = Mode::FunctionSet as u8 | BitMode::Bit4 as u8;
-        self.write_4_bits(mode_4bit)?;
+        let bit_mode = match self.transport.is_eight_bit() {
+            true => BitMode::Bit8,
+            false => BitMode::Bit4,
+        };
+
+        // Switch to 4 bit mode, the function set below is then sent as two nibbles
+        if !self.transport.is_eight_bit() {
+            self.write_4_bits(Mode::FunctionSet as u8 | BitMode::Bit4 as u8)?;
+        }
 
         // Function set command
-        self.write_command(Mode::FunctionSet as u8 | Rows::TwoLines as u8 | Font::FiveByEleven as u8, Mode::Command)?;
+        // 4 row displays are driven as 2 long lines
+        let rows = match self.geometry.rows() {
+            1 => Rows::OneLine,
+            _ => Rows::TwoLines,
+        };
+
+        self.write_command(Mode::FunctionSet as u8 | bit_mode as u8 | rows as u8 | self.font as u8)?;
 
         let initialization_code = 0
             | DisplayControl::DisplayOn as u8
             | DisplayControl::CursorOn as u8
             | DisplayControl::CursorBlink as u8;
 
-        self.write_command(Mode::DisplayControl as u8 | initialization_code, Mode::Command)?;
+        self.write_command(Mode::DisplayControl as u8 | initialization_code)?;
+        self.write_entry_mode()?;
 
         self.cursor(false)?;
         self.cursor_blink(false)?;
@@ -137,7 +471,7 @@ impl<'d, DELAY> LCD<'d, DELAY> where DELAY: Fn(u32) -> () {
         Ok(())
     }
 
-    pub fn cursor(&mut self, state: bool) -> Result<(), EspError> {
+    pub fn cursor(&mut self, state: bool) -> Result<(), LcdError> {
         self.show_cursor = state;
 
         let mut data = DisplayControl::DisplayOn as u8;
@@ -150,60 +484,565 @@ impl<'d, DELAY> LCD<'d, DELAY> where DELAY: Fn(u32) -> () {
             data |= DisplayControl::CursorOn as u8
         }
 
-        self.write_command(Mode::DisplayControl as u8 | data, Mode::Command)?;
+        self.write_command(Mode::DisplayControl as u8 | data)?;
 
         Ok(())
     }
 
-    pub fn cursor_blink(&mut self, state: bool) -> Result<(), EspError> {
+    pub fn cursor_blink(&mut self, state: bool) -> Result<(), LcdError> {
         self.blink_cursor = state;
         self.cursor(self.show_cursor)?;
 
         Ok(())
     }
 
-    fn write_4_bits(&mut self, data: u8) -> Result<(), EspError> {
-        self.driver.write(self.address, &[data | DisplayControl::DisplayOn as u8 | self.backlight as u8], BLOCK)?;
-        (self.delay)(1);
+    fn write_4_bits(&mut self, data: u8) -> Result<(), LcdError> {
+        self.transport.write_nibble(data)?;
+        (self.delay)(COMMAND_DELAY_US);
+
+        Ok(())
+    }
+
+    fn write_command(&mut self, data: u8) -> Result<(), LcdError> {
+        self.write_bytes(&[data], Register::Instruction)
+    }
 
-        self.driver.write(self.address, &[DisplayControl::Off as u8 | self.backlight as u8], BLOCK)?;
-        (self.delay)(5);
+    // Sends the bytes in one go, the transport takes care of the time between them
+    fn write_bytes(&mut self, data: &[u8], register: Register) -> Result<(), LcdError> {
+        self.transport.write(data, register)?;
+        self.wait(COMMAND_DELAY_US)?;
 
         Ok(())
     }
 
-    fn write_command(&mut self, data: u8, mode: Mode) -> Result<(), EspError> {
-        let high_bits: u8 = data & 0b1111_0000;
-        let low_bits: u8 = (data << 4) & 0b1111_0000;
+    pub fn clear(&mut self) -> Result<(), LcdError> {
+        self.write_command(Command::Clear as u8)?;
+        self.wait(HOME_DELAY_US)?;
+        self.cursor_address = 0;
 
-        self.write_4_bits(high_bits | mode as u8)?;
-        self.write_4_bits(low_bits | mode as u8)?;
+        // Clear also switches the entry mode back to increment
+        if self.text_direction == TextDirection::RightToLeft {
+            self.write_entry_mode()?;
+        }
 
         Ok(())
     }
 
-    pub fn clear(&mut self) -> Result<(), EspError> {
-        self.write_command(Command::Clear as u8, Mode::Command)?;
+    /// Moves the cursor to the first character and undoes any scrolling.
+    pub fn home(&mut self) -> Result<(), LcdError> {
+        self.write_command(Command::ReturnHome as u8)?;
+        self.wait(HOME_DELAY_US)?;
+        self.cursor_address = 0;
 
         Ok(())
     }
 
-    pub fn reset(&mut self) -> Result<(), EspError> {
+    pub fn reset(&mut self) -> Result<(), LcdError> {
         self.clear()?;
         self.cursor_move_to(0, 0)?;
 
         Ok(())
     }
 
-    pub fn cursor_move_to(&mut self, row: u8, column: u8) -> Result<(), EspError> {
-        self.write_command(Command::ReturnHome as u8, Mode::Command)?;
+    pub fn geometry(&self) -> Geometry {
+        self.geometry
+    }
+
+    pub fn cursor_move_to(&mut self, row: u8, column: u8) -> Result<(), LcdError> {
+        let offset = self.geometry.row_offsets().get(row as usize).copied();
+
+        match offset {
+            Some(offset) if column < self.geometry.columns() => self.set_cursor_address(offset + column)?,
+            _ => return Err(LcdError::OutOfBounds { row, column }),
+        }
+
+        Ok(())
+    }
+
+    fn set_cursor_address(&mut self, address: u8) -> Result<(), LcdError> {
+        self.write_command(Command::SetDdramAddress as u8 | address)?;
+        self.cursor_address = address;
+
+        Ok(())
+    }
+
+    // Writes characters to DDRAM, moving the cursor address along the same way the LCD does
+    fn write_data(&mut self, characters: &[u8]) -> Result<(), LcdError> {
+        self.write_bytes(characters, Register::Data)?;
+
+        let forward = self.text_direction == TextDirection::LeftToRight;
+
+        for _ in characters {
+            self.cursor_address = self.next_address(self.cursor_address, forward);
+        }
+
+        Ok(())
+    }
+
+    // Writes the characters in reading order from the given position, whatever the text direction.
+    // Right to left the address counter goes down after every character, so the run is sent backwards
+    // starting from its last column, the same way read_ddram reads it.
+    pub(crate) fn write_at(&mut self, row: u8, column: u8, characters: &[u8]) -> Result<(), LcdError> {
+        match self.text_direction {
+            TextDirection::LeftToRight => {
+                self.cursor_move_to(row, column)?;
+                self.write_data(characters)?;
+            }
+            TextDirection::RightToLeft => {
+                let last = u8::try_from(characters.len().saturating_sub(1))
+                    .ok()
+                    .and_then(|length| column.checked_add(length));
 
-        let shift: u8 = row * 40 + column;
+                self.cursor_move_to(row, last.unwrap_or(u8::MAX))?;
 
-        for _ in 0..shift {
-            self.write_command(Command::ShiftCursor as u8 | Direction::Right as u8, Mode::Command)?;
+                let reversed: Vec<u8> = characters.iter().rev().copied().collect();
+                self.write_data(&reversed)?;
+            }
         }
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    // The DDRAM address after the given one, wrapping the same way the LCD does.
+    // A single line goes through all 80 characters, two lines hold 40 characters each.
+    fn next_address(&self, address: u8, forward: bool) -> u8 {
+        match (self.geometry.rows(), forward, address) {
+            (1, true, 0x4F) => 0x00,
+            (1, true, address) => address + 1,
+            (1, false, 0x00) => 0x4F,
+            (1, false, address) => address - 1,
+            (_, true, 0x27) => 0x40,
+            (_, true, 0x67) => 0x00,
+            (_, true, address) => address + 1,
+            (_, false, 0x00) => 0x67,
+            (_, false, 0x40) => 0x27,
+            (_, false, address) => address - 1,
+        }
+    }
+
+    /// Stores a 5x8 glyph in one of the 8 CGRAM slots, only the lower 5 bits of each row are used.
+    pub fn create_char(&mut self, slot: u8, glyph: &Glyph) -> Result<(), LcdError> {
+        self.store_glyph(slot, glyph)?;
+        self.glyph_set = GlyphSet::Custom;
+
+        Ok(())
+    }
+
+    /// Writes the custom character stored in the given slot at the cursor position.
+    pub fn write_custom_char(&mut self, slot: u8) -> Result<(), LcdError> {
+        if slot >= CUSTOM_CHARACTERS {
+            return Err(LcdError::InvalidSlot(slot));
+        }
+
+        self.write_data(&[slot])?;
+
+        Ok(())
+    }
+
+    /// Draws a horizontal bar `width` characters long, filled in proportion to `value / max` with a
+    /// resolution of one pixel column. Replaces any custom characters with the partial block glyphs.
+    /// Fails when the bar doesn't fit in the row, the LCD would carry on writing to the next one.
+    pub fn draw_bar(&mut self, row: u8, column: u8, width: u8, value: u32, max: u32) -> Result<(), LcdError> {
+        match column.checked_add(width) {
+            Some(end) if end <= self.geometry.columns() => {}
+            _ => return Err(LcdError::OutOfBounds { row, column: column.saturating_add(width) }),
+        }
+
+        self.load_glyph_set(GlyphSet::Bar)?;
+
+        let total = width as u32 * CHARACTER_WIDTH;
+        let filled = match max {
+            0 => 0,
+            max => value.min(max) * total / max,
+        };
+
+        let characters: Vec<u8> = (0..width as u32)
+            .map(|cell| match filled.saturating_sub(cell * CHARACTER_WIDTH) {
+                0 => BLANK,
+                columns if columns >= CHARACTER_WIDTH => FULL_BLOCK,
+                // Partial blocks are stored in the slots from 0 (1 column) to 3 (4 columns)
+                columns => columns as u8 - 1,
+            })
+            .collect();
+
+        self.write_at(row, column, &characters)?;
+
+        Ok(())
+    }
+
+    /// Writes digits 3 characters wide spanning both rows, starting at the given column.
+    /// Spaces leave a gap, anything else fails. Replaces any custom characters with the big digit glyphs.
+    /// Nothing is written when a digit is invalid or the digits don't fit on the display.
+    pub fn write_big_digits(&mut self, column: u8, digits: &str) -> Result<(), LcdError> {
+        let glyphs = digits
+            .chars()
+            .map(|char| match char {
+                ' ' => Ok([[BLANK; 3]; 2]),
+                char => char
+                    .to_digit(10)
+                    .and_then(|digit| BIG_DIGITS.get(digit as usize))
+                    .copied()
+                    .ok_or(LcdError::InvalidDigit(char)),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if self.geometry.rows() < 2 {
+            return Err(LcdError::OutOfBounds { row: 1, column });
+        }
+
+        // The space after the last digit doesn't have to fit
+        let end = u8::try_from(glyphs.len())
+            .ok()
+            .and_then(|count| count.checked_mul(BIG_DIGIT_WIDTH))
+            .and_then(|width| column.checked_add(width.saturating_sub(1)));
+
+        match end {
+            Some(end) if end <= self.geometry.columns() => {}
+            _ => return Err(LcdError::OutOfBounds { row: 0, column: end.unwrap_or(u8::MAX) }),
+        }
+
+        self.load_glyph_set(GlyphSet::BigDigits)?;
+
+        for (index, rows) in glyphs.iter().enumerate() {
+            for (row, characters) in rows.iter().enumerate() {
+                self.write_at(row as u8, column + index as u8 * BIG_DIGIT_WIDTH, characters)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn load_glyph_set(&mut self, glyph_set: GlyphSet) -> Result<(), LcdError> {
+        if self.glyph_set == glyph_set {
+            return Ok(());
+        }
+
+        let glyphs: &[Glyph] = match glyph_set {
+            GlyphSet::Bar => &BAR_GLYPHS,
+            GlyphSet::BigDigits => &BIG_DIGIT_GLYPHS,
+            GlyphSet::Custom | GlyphSet::Charset => &[],
+        };
+
+        for (slot, glyph) in glyphs.iter().enumerate() {
+            self.store_glyph(slot as u8, glyph)?;
+        }
+
+        self.glyph_set = glyph_set;
+
+        Ok(())
+    }
+
+    fn store_glyph(&mut self, slot: u8, glyph: &Glyph) -> Result<(), LcdError> {
+        if slot >= CUSTOM_CHARACTERS {
+            return Err(LcdError::InvalidSlot(slot));
+        }
+
+        let mut rows: Vec<u8> = glyph.iter().map(|row| row & 0b0001_1111).collect();
+
+        // The address counter goes down right to left in CGRAM as well, so the rows are sent from the bottom one
+        let address = match self.text_direction {
+            TextDirection::LeftToRight => slot << 3,
+            TextDirection::RightToLeft => {
+                rows.reverse();
+                slot << 3 | (rows.len() as u8 - 1)
+            }
+        };
+
+        self.write_command(Command::SetCgramAddress as u8 | address)?;
+        self.write_bytes(&rows, Register::Data)?;
+
+        // Writing to CGRAM moved the address counter away from the cursor
+        self.set_cursor_address(self.cursor_address)?;
+
+        Ok(())
+    }
+}
+
+impl<TRANSPORT, DELAY> std::fmt::Write for LCD<TRANSPORT, DELAY> where TRANSPORT: Transport, DELAY: Fn(u32) -> () {
+    fn write_str(&mut self, text: &str) -> std::fmt::Result {
+        LCD::write_str(self, text).map_err(|_| std::fmt::Error)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::backpack::Backpack;
+    use crate::charset::Rom;
+    use crate::emulator::Emulator;
+    use crate::framebuffer::BufferedLCD;
+    use crate::glyphs::{BAR_GLYPHS, BIG_DIGIT_GLYPHS, BIG_DIGITS, Glyph};
+    use crate::lcd::{Geometry, LCD, LcdError, TextDirection};
+    use crate::marquee::Marquee;
+
+    type EmulatedLCD = LCD<Backpack<Emulator>, fn(u32)>;
+
+    const SMILEY: Glyph = [0b00000, 0b01010, 0b01010, 0b00000, 0b10001, 0b01110, 0b00000, 0b00000];
+
+    fn no_delay(_: u32) {}
+
+    fn initialized(geometry: Geometry) -> EmulatedLCD {
+        let mut lcd: EmulatedLCD = LCD::with_transport(Backpack::new(Emulator::new(Rom::A00)), geometry, no_delay);
+        lcd.initialize().unwrap();
+        lcd
+    }
+
+    fn emulator(lcd: &EmulatedLCD) -> &Emulator {
+        lcd.transport().port()
+    }
+
+    fn render(lcd: &EmulatedLCD) -> Vec<String> {
+        emulator(lcd).render(lcd.geometry().columns(), lcd.geometry().rows())
+    }
+
+    #[test]
+    fn initializes_in_four_bit_mode() {
+        let lcd = initialized(Geometry::Columns16Rows2);
+        let emulator = emulator(&lcd);
+
+        assert!(emulator.is_four_bit());
+        assert!(emulator.is_two_lines());
+        assert!(emulator.is_display_on());
+        assert!(!emulator.is_cursor_on());
+        assert_eq!(emulator.address(), 0x00);
+        assert_eq!(render(&lcd), [" ".repeat(16), " ".repeat(16)]);
+
+        assert!(!initialized(Geometry::Columns16Rows1).transport().port().is_two_lines());
+        assert!(initialized(Geometry::Columns16Rows1Split).transport().port().is_two_lines());
+    }
+
+    #[test]
+    fn writes_text_on_16x2() {
+        let mut lcd = initialized(Geometry::Columns16Rows2);
+
+        lcd.write_str("Hello").unwrap();
+        lcd.cursor_move_to(1, 3).unwrap();
+        lcd.write_str("World").unwrap();
+
+        assert_eq!(render(&lcd), ["Hello           ", "   World        "]);
+        assert_eq!(emulator(&lcd).address(), 0x48);
+        assert_eq!(emulator(&lcd).cursor(lcd.geometry()), Some((1, 8)));
+    }
+
+    #[test]
+    fn writes_text_on_20x4() {
+        let mut lcd = initialized(Geometry::Columns20Rows4);
+
+        for row in 0..4 {
+            lcd.cursor_move_to(row, row).unwrap();
+            lcd.write_str(&format!("row {}", row)).unwrap();
+        }
+
+        assert_eq!(render(&lcd), [
+            "row 0               ",
+            " row 1              ",
+            "  row 2             ",
+            "   row 3            ",
+        ]);
+
+        lcd.cursor_move_to(3, 4).unwrap();
+        assert_eq!(emulator(&lcd).address(), 0x58);
+        assert_eq!(emulator(&lcd).cursor(lcd.geometry()), Some((3, 4)));
+
+        lcd.cursor_move_to(2, 19).unwrap();
+        assert_eq!(emulator(&lcd).address(), 0x27);
+        assert_eq!(emulator(&lcd).cursor(lcd.geometry()), Some((2, 19)));
+    }
+
+    #[test]
+    fn rejects_positions_outside_the_display() {
+        let mut lcd = initialized(Geometry::Columns20Rows4);
+
+        assert!(matches!(lcd.cursor_move_to(4, 0), Err(LcdError::OutOfBounds { row: 4, column: 0 })));
+        assert!(matches!(lcd.cursor_move_to(0, 20), Err(LcdError::OutOfBounds { row: 0, column: 20 })));
+
+        let mut lcd = initialized(Geometry::Columns16Rows1Split);
+
+        lcd.cursor_move_to(1, 7).unwrap();
+        assert_eq!(emulator(&lcd).address(), 0x47);
+        assert!(lcd.cursor_move_to(0, 8).is_err());
+    }
+
+    #[test]
+    fn stores_custom_characters() {
+        let mut lcd = initialized(Geometry::Columns16Rows2);
+
+        lcd.cursor_move_to(0, 2).unwrap();
+        lcd.create_char(3, &SMILEY).unwrap();
+        lcd.write_custom_char(3).unwrap();
+
+        assert_eq!(emulator(&lcd).glyph(3), SMILEY);
+        assert_eq!(render(&lcd)[0], "  ④             ");
+        assert_eq!(emulator(&lcd).cursor(lcd.geometry()), Some((0, 3)));
+        assert!(matches!(lcd.create_char(8, &SMILEY), Err(LcdError::InvalidSlot(8))));
+        assert!(matches!(lcd.write_custom_char(8), Err(LcdError::InvalidSlot(8))));
+    }
+
+    #[test]
+    fn draws_bars_within_the_row() {
+        let mut lcd = initialized(Geometry::Columns20Rows4);
+
+        // 11 of the 20 pixel columns, two full blocks and a single column
+        lcd.draw_bar(3, 16, 4, 55, 100).unwrap();
+
+        assert_eq!(&emulator(&lcd).ddram()[0x64..0x68], &[0xFF, 0xFF, 0x00, b' ']);
+        assert_eq!(emulator(&lcd).glyph(0), BAR_GLYPHS[0]);
+        assert!(matches!(lcd.draw_bar(3, 15, 10, 37, 100), Err(LcdError::OutOfBounds { row: 3, column: 25 })));
+        assert!(lcd.draw_bar(0, 250, 10, 37, 100).is_err());
+    }
+
+    #[test]
+    fn writes_big_digits_over_both_rows() {
+        let mut lcd = initialized(Geometry::Columns16Rows2);
+
+        lcd.write_big_digits(2, "1 2").unwrap();
+
+        assert_eq!(emulator(&lcd).glyph(0), BIG_DIGIT_GLYPHS[0]);
+        assert_eq!(&emulator(&lcd).ddram()[0x02..0x05], &BIG_DIGITS[1][0]);
+        assert_eq!(&emulator(&lcd).ddram()[0x42..0x45], &BIG_DIGITS[1][1]);
+        assert_eq!(&emulator(&lcd).ddram()[0x06..0x09], b"   ");
+        assert_eq!(&emulator(&lcd).ddram()[0x0A..0x0D], &BIG_DIGITS[2][0]);
+        assert_eq!(&emulator(&lcd).ddram()[0x4A..0x4D], &BIG_DIGITS[2][1]);
+
+        // The last digit can end on the last column
+        lcd.write_big_digits(13, "8").unwrap();
+        assert_eq!(&emulator(&lcd).ddram()[0x4D..0x50], &BIG_DIGITS[8][1]);
+    }
+
+    #[test]
+    fn rejects_big_digits_that_do_not_fit() {
+        let mut lcd = initialized(Geometry::Columns16Rows2);
+        let instructions = emulator(&lcd).instructions();
+
+        // The second digit would take columns 18 to 20
+        assert!(matches!(lcd.write_big_digits(14, "12"), Err(LcdError::OutOfBounds { row: 0, column: 21 })));
+        assert!(matches!(lcd.write_big_digits(250, "1"), Err(LcdError::OutOfBounds { row: 0, column: 253 })));
+        assert!(matches!(lcd.write_big_digits(0, &"1".repeat(100)), Err(LcdError::OutOfBounds { row: 0, column: 255 })));
+        assert!(matches!(lcd.write_big_digits(0, "1x"), Err(LcdError::InvalidDigit('x'))));
+
+        // Nothing was sent to the display, not even the glyphs
+        assert_eq!(emulator(&lcd).instructions(), instructions);
+        assert_eq!(render(&lcd), [" ".repeat(16), " ".repeat(16)]);
+
+        let mut lcd = initialized(Geometry::Columns16Rows1);
+        assert!(matches!(lcd.write_big_digits(0, "1"), Err(LcdError::OutOfBounds { row: 1, column: 0 })));
+    }
+
+    #[test]
+    fn positioned_writes_ignore_the_text_direction() {
+        let mut lcd = initialized(Geometry::Columns20Rows4);
+        lcd.text_direction(TextDirection::RightToLeft).unwrap();
+
+        lcd.create_char(1, &SMILEY).unwrap();
+        assert_eq!(emulator(&lcd).glyph(1), SMILEY);
+
+        lcd.draw_bar(3, 16, 4, 55, 100).unwrap();
+        assert_eq!(emulator(&lcd).glyph(0), BAR_GLYPHS[0]);
+        assert_eq!(&emulator(&lcd).ddram()[0x64..0x68], &[0xFF, 0xFF, 0x00, b' ']);
+
+        lcd.write_big_digits(0, "12").unwrap();
+        assert_eq!(emulator(&lcd).glyph(0), BIG_DIGIT_GLYPHS[0]);
+        assert_eq!(&emulator(&lcd).ddram()[0x00..0x03], &BIG_DIGITS[1][0]);
+        assert_eq!(&emulator(&lcd).ddram()[0x44..0x47], &BIG_DIGITS[2][1]);
+
+        let mut marquee = Marquee::new(2, "Back to front", Duration::ZERO);
+        marquee.update(&mut lcd).unwrap();
+        assert_eq!(render(&lcd)[2], "Back to front       ");
+
+        // Text written at the cursor still goes right to left
+        lcd.cursor_move_to(2, 19).unwrap();
+        lcd.write_str("ab").unwrap();
+        assert_eq!(&render(&lcd)[2][18..], "ba");
+    }
+
+    #[test]
+    fn reads_characters_back() {
+        let mut lcd = initialized(Geometry::Columns20Rows4);
+
+        lcd.cursor_move_to(3, 0).unwrap();
+        lcd.write_str("Read me back").unwrap();
+        lcd.cursor_move_to(1, 2).unwrap();
+
+        assert_eq!(lcd.read_ddram(3, 5, 2).unwrap(), b"me");
+        assert_eq!(lcd.read_row(3).unwrap(), "Read me back        ");
+        assert_eq!(lcd.read_ddram(3, 20, 0).unwrap(), b"");
+        assert_eq!(emulator(&lcd).cursor(lcd.geometry()), Some((1, 2)));
+
+        lcd.text_direction(TextDirection::RightToLeft).unwrap();
+        assert_eq!(lcd.read_ddram(3, 0, 4).unwrap(), b"Read");
+    }
+
+    #[test]
+    fn rejects_reads_past_the_row() {
+        let mut lcd = initialized(Geometry::Columns20Rows4);
+
+        assert!(matches!(lcd.read_ddram(3, 15, 10), Err(LcdError::OutOfBounds { row: 3, column: 25 })));
+        assert!(matches!(lcd.read_ddram(0, 200, 100), Err(LcdError::OutOfBounds { row: 0, column: 255 })));
+        assert!(matches!(lcd.read_ddram(4, 0, 1), Err(LcdError::OutOfBounds { row: 4, column: 1 })));
+    }
+
+    #[test]
+    fn flush_only_sends_changes() {
+        let mut display = BufferedLCD::new(initialized(Geometry::Columns16Rows2));
+
+        display.write_str("Hello").unwrap();
+        display.cursor_move_to(1, 0).unwrap();
+        display.write_str("World").unwrap();
+        display.flush().unwrap();
+
+        assert_eq!(render(display.lcd()), ["Hello           ", "World           "]);
+
+        let instructions = emulator(display.lcd()).instructions();
+
+        display.cursor_move_to(0, 1).unwrap();
+        display.write_str("a").unwrap();
+        display.flush().unwrap();
+
+        // One address command and one character
+        assert_eq!(emulator(display.lcd()).instructions(), instructions + 2);
+        assert_eq!(render(display.lcd())[0], "Hallo           ");
+
+        display.flush().unwrap();
+        assert_eq!(emulator(display.lcd()).instructions(), instructions + 2);
+    }
+
+    #[test]
+    fn flush_writes_left_to_right_in_either_direction() {
+        let mut display = BufferedLCD::new(initialized(Geometry::Columns16Rows2));
+        display.lcd().text_direction(TextDirection::RightToLeft).unwrap();
+
+        display.write_str("Hi").unwrap();
+        display.cursor_move_to(1, 12).unwrap();
+        display.write_str("1234").unwrap();
+        display.flush().unwrap();
+
+        assert_eq!(render(display.lcd()), ["Hi              ", "            1234"]);
+
+        display.cursor_move_to(1, 13).unwrap();
+        display.write_str("56").unwrap();
+        display.flush().unwrap();
+
+        assert_eq!(render(display.lcd())[1], "            1564");
+    }
+
+    #[test]
+    fn marquee_scrolls_its_row() {
+        let mut lcd = initialized(Geometry::Columns16Rows2);
+        lcd.write_str("Title").unwrap();
+
+        let mut marquee = Marquee::new(1, "A line too long for the display", Duration::ZERO);
+
+        assert!(marquee.update(&mut lcd).unwrap());
+        assert_eq!(render(&lcd), ["Title           ", "A line too long "]);
+
+        assert!(marquee.update(&mut lcd).unwrap());
+        assert_eq!(render(&lcd), ["Title           ", " line too long f"]);
+
+        let mut marquee = Marquee::new(1, "Fits", Duration::ZERO);
+
+        assert!(marquee.update(&mut lcd).unwrap());
+        assert!(!marquee.update(&mut lcd).unwrap());
+        assert_eq!(render(&lcd)[1], "Fits            ");
+    }
+}
@@ -0,0 +1,12 @@
+pub mod glyphs;
+pub mod charset;
+pub mod emulator;
+pub mod lcd;
+pub mod transport;
+pub mod backpack;
+pub mod framebuffer;
+pub mod marquee;
+#[cfg(feature = "esp")]
+pub mod i2c;
+#[cfg(feature = "esp")]
+pub mod parallel;
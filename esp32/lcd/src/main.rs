@@ -1,9 +1,14 @@
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
 use anyhow::anyhow;
-use esp_idf_hal::delay::FreeRtos;
+use esp_idf_hal::delay::{Ets, FreeRtos};
 use esp_idf_hal::prelude::{FromValueType, Peripherals};
-use crate::lcd::Direction;
+use lcd::lcd::{Geometry, LCD};
+use lcd::marquee::Marquee;
 
-mod lcd;
+// How many times the benchmark fills a row
+const BENCHMARK_ROUNDS: u32 = 20;
 
 fn main() -> anyhow::Result<()> {
     esp_idf_sys::link_patches();
@@ -14,34 +19,36 @@ fn main() -> anyhow::Result<()> {
     let sda = peripherals.pins.gpio2;
     let scl = peripherals.pins.gpio1;
 
-    let mut display = lcd::LCD::new(peripherals.i2c1, sda, scl, FreeRtos::delay_ms)?;
+    // Finds the backpack whichever address it is jumpered to, use LCD::new when the address is known
+    let mut display = LCD::probe(peripherals.i2c1, sda, scl, Geometry::Columns16Rows2, Ets::delay_us)?;
+
+    println!("found LCD at {:#04x}", display.address());
+
+    // Measure how long it takes to fill a row
+    let started = Instant::now();
+
+    for _ in 0..BENCHMARK_ROUNDS {
+        display.cursor_move_to(0, 0)?;
+        display.write_str("0123456789ABCDEF")?;
+    }
+
+    println!("filling a row takes {}us", started.elapsed().as_micros() / BENCHMARK_ROUNDS as u128);
+    println!("read back \"{}\"", display.read_row(0)?);
+
+    // The backpack wires the R/W line, so clearing waits on the busy flag rather than the 1.52ms from the datasheet.
+    // Polling takes a few I2C transactions, so it is slower than sleeping for the other instructions.
+    display.busy_polling(true);
+
+    display.clear()?;
 
-    display.initialize()?;
-    display.write_str("Hello")?;
-    display.cursor_move_to(1, 0)?;
-    display.write_str("World")?;
+    // The first row stays put while the second one scrolls on its own
+    write!(display, "Hello {}", "World")?;
 
-    let mut scroll_direction = lcd::Direction::Right;
-    let mut counter = 0;
+    let mut marquee = Marquee::new(1, "This row scrolls on its own while the first one stays still", Duration::from_millis(400));
 
     loop {
-        match scroll_direction {
-            Direction::Right => {
-                counter += 1;
-            }
-            Direction::Left => {
-                counter -= 1;
-            }
-        };
-
-        FreeRtos::delay_ms(400);
-        display.scroll(scroll_direction)?;
-
-        scroll_direction = match counter {
-            value if value >= 11 => Direction::Left,
-            value if value <= 0 => Direction::Right,
-            _ => scroll_direction
-        };
+        marquee.update(&mut display)?;
+
+        FreeRtos::delay_ms(10);
     }
 }
-
@@ -0,0 +1,75 @@
+use std::time::{Duration, Instant};
+
+use crate::lcd::{LCD, LcdError};
+use crate::transport::Transport;
+
+// Spaces between the end of the text and its start coming around again
+const GAP: usize = 4;
+
+/// Scrolls text that is too long for a row from right to left, leaving the other rows alone.
+/// Unlike `LCD::scroll`, which shifts the whole display, only the characters of its row are rewritten.
+pub struct Marquee {
+    row: u8,
+    text: Vec<char>,
+    step: Duration,
+    offset: usize,
+    // When the row was last drawn, None until the first update
+    drawn: Option<Instant>,
+}
+
+impl Marquee {
+    /// `step` is how long each position is shown for.
+    pub fn new(row: u8, text: &str, step: Duration) -> Self {
+        Self {
+            row,
+            text: text.chars().collect(),
+            step,
+            offset: 0,
+            drawn: None,
+        }
+    }
+
+    /// Replaces the text, starting again from its beginning.
+    pub fn set_text(&mut self, text: &str) {
+        self.text = text.chars().collect();
+        self.offset = 0;
+        self.drawn = None;
+    }
+
+    /// Draws the next position once the step has passed, meant to be called from the main loop.
+    /// Text that fits in the row is drawn once and doesn't move. Returns true when the row was redrawn.
+    pub fn update<TRANSPORT, DELAY>(&mut self, lcd: &mut LCD<TRANSPORT, DELAY>) -> Result<bool, LcdError>
+    where
+        TRANSPORT: Transport,
+        DELAY: Fn(u32) -> (),
+    {
+        let columns = lcd.geometry().columns() as usize;
+        let fits = self.text.len() <= columns;
+
+        match self.drawn {
+            Some(_) if fits => return Ok(false),
+            Some(drawn) if drawn.elapsed() < self.step => return Ok(false),
+            Some(_) => self.offset = (self.offset + 1) % (self.text.len() + GAP),
+            None => {}
+        }
+
+        let window: Vec<char> = match fits {
+            true => self.text.iter().copied().chain(std::iter::repeat(' ')).take(columns).collect(),
+            false => self.text
+                .iter()
+                .copied()
+                .chain(std::iter::repeat_n(' ', GAP))
+                .cycle()
+                .skip(self.offset)
+                .take(columns)
+                .collect(),
+        };
+
+        let characters = window.into_iter().map(|char| lcd.encode(char)).collect::<Result<Vec<_>, _>>()?;
+        lcd.write_at(self.row, 0, &characters)?;
+
+        self.drawn = Some(Instant::now());
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,193 @@
+use esp_idf_hal::delay::Ets;
+use esp_idf_hal::gpio::{AnyIOPin, AnyOutputPin, InputOutput, Output, PinDriver, Pull};
+
+use crate::lcd::{Backlight, COMMAND_DELAY_US, LcdError};
+use crate::transport::{Register, Transport};
+
+// The enable pulse has to be at least 450ns wide, with the data held a little after it goes low
+const PULSE_US: u32 = 1;
+
+/// An LCD wired straight to GPIOs, either on D4-D7 in 4 bit mode or on D0-D7 in 8 bit mode.
+/// Get the pins with `downgrade()` or `downgrade_output()`, such as `peripherals.pins.gpio4.downgrade()`.
+pub struct Parallel<'d> {
+    rs: PinDriver<'d, AnyOutputPin, Output>,
+    rw: PinDriver<'d, AnyOutputPin, Output>,
+    enable: PinDriver<'d, AnyOutputPin, Output>,
+    // D4-D7 or D0-D7, lowest bit first
+    data: Vec<PinDriver<'d, AnyIOPin, InputOutput>>,
+    // Usually the backlight is wired to the supply, a pin can switch it through a transistor
+    backlight: Option<PinDriver<'d, AnyOutputPin, Output>>,
+}
+
+impl<'d> Parallel<'d> {
+    /// Data pins are D4 to D7.
+    pub fn four_bit(
+        rs: AnyOutputPin,
+        rw: AnyOutputPin,
+        enable: AnyOutputPin,
+        data: [AnyIOPin; 4],
+        backlight: Option<AnyOutputPin>,
+    ) -> Result<Self, LcdError> {
+        Self::from_pins(rs, rw, enable, data.into(), backlight)
+    }
+
+    /// Data pins are D0 to D7.
+    pub fn eight_bit(
+        rs: AnyOutputPin,
+        rw: AnyOutputPin,
+        enable: AnyOutputPin,
+        data: [AnyIOPin; 8],
+        backlight: Option<AnyOutputPin>,
+    ) -> Result<Self, LcdError> {
+        Self::from_pins(rs, rw, enable, data.into(), backlight)
+    }
+
+    fn from_pins(
+        rs: AnyOutputPin,
+        rw: AnyOutputPin,
+        enable: AnyOutputPin,
+        data: Vec<AnyIOPin>,
+        backlight: Option<AnyOutputPin>,
+    ) -> Result<Self, LcdError> {
+        let mut enable = PinDriver::output(enable)?;
+        enable.set_low()?;
+
+        let mut data_pins = Vec::with_capacity(data.len());
+
+        // Open drain so the LCD can drive the lines while it is read, the pull-ups hold them high otherwise.
+        // Reading a display powered from 5V needs level shifters or it will drive 5V into the pins.
+        for pin in data {
+            let mut pin = PinDriver::input_output_od(pin)?;
+            pin.set_pull(Pull::Up)?;
+            data_pins.push(pin);
+        }
+
+        let backlight = match backlight {
+            Some(pin) => {
+                let mut pin = PinDriver::output(pin)?;
+                pin.set_high()?;
+                Some(pin)
+            }
+            None => None,
+        };
+
+        Ok(Self {
+            rs: PinDriver::output(rs)?,
+            rw: PinDriver::output(rw)?,
+            enable,
+            data: data_pins,
+            backlight,
+        })
+    }
+
+    // Puts the lowest bits of the value on the data pins
+    fn set_data(&mut self, bits: u8) -> Result<(), LcdError> {
+        for (index, pin) in self.data.iter_mut().enumerate() {
+            pin.set_level((bits >> index & 1 == 1).into())?;
+        }
+
+        Ok(())
+    }
+
+    fn get_data(&self) -> u8 {
+        self.data
+            .iter()
+            .enumerate()
+            .fold(0, |bits, (index, pin)| bits | (pin.is_high() as u8) << index)
+    }
+
+    fn pulse(&mut self) -> Result<(), LcdError> {
+        self.enable.set_high()?;
+        Ets::delay_us(PULSE_US);
+        self.enable.set_low()?;
+        Ets::delay_us(PULSE_US);
+
+        Ok(())
+    }
+
+    fn select(&mut self, register: Register, read: bool) -> Result<(), LcdError> {
+        self.rs.set_level((register == Register::Data).into())?;
+        self.rw.set_level(read.into())?;
+
+        Ok(())
+    }
+}
+
+impl<'d> Transport for Parallel<'d> {
+    fn is_eight_bit(&self) -> bool {
+        self.data.len() == 8
+    }
+
+    fn write_nibble(&mut self, nibble: u8) -> Result<(), LcdError> {
+        self.select(Register::Instruction, false)?;
+
+        match self.is_eight_bit() {
+            true => self.set_data(nibble & 0b1111_0000)?,
+            false => self.set_data(nibble >> 4)?,
+        }
+
+        self.pulse()
+    }
+
+    fn write(&mut self, bytes: &[u8], register: Register) -> Result<(), LcdError> {
+        self.select(register, false)?;
+
+        for (index, byte) in bytes.iter().enumerate() {
+            // Unlike I2C toggling the pins is fast, so the LCD needs time to run each byte
+            if index > 0 {
+                Ets::delay_us(COMMAND_DELAY_US);
+            }
+
+            match self.is_eight_bit() {
+                true => {
+                    self.set_data(*byte)?;
+                    self.pulse()?;
+                }
+                false => {
+                    self.set_data(byte >> 4)?;
+                    self.pulse()?;
+                    self.set_data(*byte)?;
+                    self.pulse()?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn read(&mut self, register: Register) -> Result<u8, LcdError> {
+        // Release the data lines before the LCD starts driving them
+        self.set_data(0xFF)?;
+        self.select(register, true)?;
+
+        let shifts: &[u8] = match self.is_eight_bit() {
+            true => &[0],
+            false => &[4, 0],
+        };
+
+        let mut value = 0;
+
+        for shift in shifts {
+            self.enable.set_high()?;
+            Ets::delay_us(PULSE_US);
+            value |= self.get_data() << shift;
+            self.enable.set_low()?;
+            Ets::delay_us(PULSE_US);
+        }
+
+        self.rw.set_low()?;
+
+        Ok(value)
+    }
+
+    fn set_backlight(&mut self, state: Backlight) -> Result<(), LcdError> {
+        if let Some(pin) = &mut self.backlight {
+            match state {
+                Backlight::On => pin.set_high()?,
+                Backlight::Off => pin.set_low()?,
+            }
+        }
+
+        Ok(())
+    }
+}
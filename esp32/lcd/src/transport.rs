@@ -0,0 +1,27 @@
+use crate::lcd::{Backlight, LcdError};
+
+/// Register selected by the RS line, instructions go to one and characters to the other.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Register {
+    Instruction,
+    Data,
+}
+
+/// Moves bytes between the driver and the HD44780, so the same commands work whether the LCD sits
+/// behind a PCF8574 backpack or is wired straight to GPIOs.
+pub trait Transport {
+    /// Whether D0-D7 are all wired, otherwise every byte is sent as two nibbles on D4-D7.
+    fn is_eight_bit(&self) -> bool;
+
+    /// Sends the upper 4 bits as an instruction in a single enable pulse, with D0-D3 low when they are wired.
+    /// Used for the function sets at power up, while the LCD may be in either mode.
+    fn write_nibble(&mut self, nibble: u8) -> Result<(), LcdError>;
+
+    /// Sends the bytes to the register, leaving at least the 37us most instructions take between two of them.
+    fn write(&mut self, bytes: &[u8], register: Register) -> Result<(), LcdError>;
+
+    /// Reads a byte from the register, the busy flag and address counter when reading the instruction register.
+    fn read(&mut self, register: Register) -> Result<u8, LcdError>;
+
+    fn set_backlight(&mut self, state: Backlight) -> Result<(), LcdError>;
+}
@@ -0,0 +1,269 @@
+use anyhow::bail;
+
+// Pixels on each side of a display
+const SIZE: usize = 8;
+
+/// How a display is turned, clockwise.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Rotation {
+    None,
+    Rotate90,
+    Rotate180,
+    Rotate270,
+}
+
+/// Mirrors a display, applied before the rotation.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum Flip {
+    None,
+    // Left and right are swapped
+    Horizontal,
+    // Top and bottom are swapped
+    Vertical,
+}
+
+/// Where a display sits in the grid and how it is mounted.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Module {
+    pub row: usize,
+    pub column: usize,
+    pub rotation: Rotation,
+    pub flip: Flip,
+}
+
+impl Module {
+    pub const fn at(row: usize, column: usize) -> Self {
+        Self { row, column, rotation: Rotation::None, flip: Flip::None }
+    }
+
+    pub const fn rotated(mut self, rotation: Rotation) -> Self {
+        self.rotation = rotation;
+        self
+    }
+
+    pub const fn flipped(mut self, flip: Flip) -> Self {
+        self.flip = flip;
+        self
+    }
+}
+
+/// Arrangement of the chained displays in a grid of rows and columns, so pixel coordinates can be given
+/// for the whole picture. Modules are listed in chain order, starting with the one wired to the ESP32.
+#[derive(Copy, Clone, Debug)]
+pub struct Layout<const DISPLAY_COUNT: usize> {
+    rows: usize,
+    columns: usize,
+    modules: [Module; DISPLAY_COUNT],
+}
+
+impl<const DISPLAY_COUNT: usize> Layout<DISPLAY_COUNT> {
+    /// Every cell of the grid has to be taken by exactly one module.
+    pub fn grid(rows: usize, columns: usize, modules: [Module; DISPLAY_COUNT]) -> anyhow::Result<Self> {
+        if rows * columns != DISPLAY_COUNT {
+            bail!("a {}x{} grid doesn't fit {} displays", rows, columns, DISPLAY_COUNT);
+        }
+
+        for (index, module) in modules.iter().enumerate() {
+            if module.row >= rows || module.column >= columns {
+                bail!("display {} is outside the {}x{} grid", index, rows, columns);
+            }
+
+            if modules[..index].iter().any(|other| other.row == module.row && other.column == module.column) {
+                bail!("display {} shares its cell with another one", index);
+            }
+        }
+
+        Ok(Self { rows, columns, modules })
+    }
+
+    /// Displays stacked in one column with the one wired to the ESP32 at the bottom.
+    pub fn column() -> Self {
+        Self {
+            rows: DISPLAY_COUNT,
+            columns: 1,
+            modules: std::array::from_fn(|index| Module::at(DISPLAY_COUNT - 1 - index, 0)),
+        }
+    }
+
+    /// Displays side by side in one row with the one wired to the ESP32 on the right, like the 4 in 1 FC-16 boards.
+    pub fn row() -> Self {
+        Self {
+            rows: 1,
+            columns: DISPLAY_COUNT,
+            modules: std::array::from_fn(|index| Module::at(0, DISPLAY_COUNT - 1 - index)),
+        }
+    }
+
+    /// Turns every display the same way.
+    pub fn rotated(mut self, rotation: Rotation) -> Self {
+        for module in &mut self.modules {
+            module.rotation = rotation;
+        }
+
+        self
+    }
+
+    /// Mirrors every display the same way.
+    pub fn flipped(mut self, flip: Flip) -> Self {
+        for module in &mut self.modules {
+            module.flip = flip;
+        }
+
+        self
+    }
+
+    pub fn width(&self) -> usize {
+        self.columns * SIZE
+    }
+
+    pub fn height(&self) -> usize {
+        self.rows * SIZE
+    }
+
+    /// Pixel shown by a bit of a digit register of the display at that position in the chain.
+    pub fn locate(&self, display: usize, digit: u8, bit: u8) -> (usize, usize) {
+        let module = self.modules[display];
+        let last = SIZE - 1;
+
+        // Unturned, each digit register drives a column of the display, with the highest bit at the top
+        let (x, y) = (digit as usize, last - bit as usize);
+
+        let (x, y) = match module.flip {
+            Flip::None => (x, y),
+            Flip::Horizontal => (last - x, y),
+            Flip::Vertical => (x, last - y),
+        };
+
+        let (x, y) = match module.rotation {
+            Rotation::None => (x, y),
+            Rotation::Rotate90 => (last - y, x),
+            Rotation::Rotate180 => (last - x, last - y),
+            Rotation::Rotate270 => (y, last - x),
+        };
+
+        (module.column * SIZE + x, module.row * SIZE + y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::layout::{Flip, Layout, Module, Rotation};
+
+    const ROTATIONS: [Rotation; 4] = [Rotation::None, Rotation::Rotate90, Rotation::Rotate180, Rotation::Rotate270];
+    const FLIPS: [Flip; 3] = [Flip::None, Flip::Horizontal, Flip::Vertical];
+
+    // Every pixel a layout covers, checking none is reached twice
+    fn pixels<const DISPLAY_COUNT: usize>(layout: &Layout<DISPLAY_COUNT>) -> Vec<bool> {
+        let mut covered = vec![false; layout.width() * layout.height()];
+
+        for display in 0..DISPLAY_COUNT {
+            for digit in 0..8 {
+                for bit in 0..8 {
+                    let (x, y) = layout.locate(display, digit, bit);
+                    let index = y * layout.width() + x;
+
+                    assert!(!covered[index], "({}, {}) is shown by two LEDs", x, y);
+                    covered[index] = true;
+                }
+            }
+        }
+
+        covered
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        // Digit 1, bit 7 is the second column of the top row on an unturned display
+        let expected = [
+            (Rotation::None, [(1, 0), (6, 0), (1, 7)]),
+            (Rotation::Rotate90, [(7, 1), (7, 6), (0, 1)]),
+            (Rotation::Rotate180, [(6, 7), (1, 7), (6, 0)]),
+            (Rotation::Rotate270, [(0, 6), (0, 1), (7, 6)]),
+        ];
+
+        for (rotation, positions) in expected {
+            for (flip, position) in FLIPS.into_iter().zip(positions) {
+                let layout = Layout::<1>::column().rotated(rotation).flipped(flip);
+
+                assert_eq!(layout.locate(0, 1, 7), position, "{:?} {:?}", rotation, flip);
+            }
+        }
+    }
+
+    #[test]
+    fn every_orientation_covers_the_display() {
+        for rotation in ROTATIONS {
+            for flip in FLIPS {
+                let layout = Layout::<1>::column().rotated(rotation).flipped(flip);
+
+                assert!(pixels(&layout).into_iter().all(|covered| covered), "{:?} {:?}", rotation, flip);
+            }
+        }
+    }
+
+    #[test]
+    fn row_puts_the_first_display_on_the_right() {
+        let layout = Layout::<4>::row();
+
+        assert_eq!((layout.width(), layout.height()), (32, 8));
+        assert_eq!(layout.locate(0, 0, 7), (24, 0));
+        assert_eq!(layout.locate(3, 7, 0), (7, 7));
+        assert!(pixels(&layout).into_iter().all(|covered| covered));
+    }
+
+    #[test]
+    fn grid_of_four() {
+        let layout = Layout::grid(2, 2, [
+            Module::at(1, 1),
+            Module::at(1, 0),
+            Module::at(0, 1).rotated(Rotation::Rotate180),
+            Module::at(0, 0).rotated(Rotation::Rotate180),
+        ]).unwrap();
+
+        assert_eq!((layout.width(), layout.height()), (16, 16));
+        assert_eq!(layout.locate(0, 0, 7), (8, 8));
+        assert_eq!(layout.locate(1, 0, 7), (0, 8));
+        assert_eq!(layout.locate(2, 0, 7), (15, 7));
+        assert_eq!(layout.locate(3, 0, 7), (7, 7));
+        assert!(pixels(&layout).into_iter().all(|covered| covered));
+    }
+
+    #[test]
+    fn grid_rejects_modules_that_do_not_fit() {
+        assert!(Layout::grid(1, 1, [Module::at(0, 0), Module::at(0, 1)]).is_err());
+        assert!(Layout::grid(1, 2, [Module::at(0, 0), Module::at(1, 0)]).is_err());
+        assert!(Layout::grid(1, 2, [Module::at(0, 1), Module::at(0, 1)]).is_err());
+    }
+
+    #[test]
+    fn column_matches_the_original_mapping() {
+        const DISPLAY_COUNT: usize = 3;
+
+        let layout = Layout::<DISPLAY_COUNT>::column();
+        let cache: Vec<u8> = (0..DISPLAY_COUNT * 64).map(|index| (index * 7 % 5 < 2) as u8).collect();
+
+        for row in 0..8u8 {
+            // Before layouts the displays were sent in cache order, the top one first
+            let mut original = [0u8; DISPLAY_COUNT];
+
+            for column in 0..8u8 {
+                for display in 0..DISPLAY_COUNT {
+                    original[display] |= cache[(display * 64) + (column * 8 + row) as usize] << (7 - column);
+                }
+            }
+
+            // Now the display furthest from the ESP32 goes first
+            let sent: Vec<u8> = (0..DISPLAY_COUNT)
+                .rev()
+                .map(|display| {
+                    (0..8u8).fold(0, |byte, bit| {
+                        let (x, y) = layout.locate(display, row, bit);
+                        byte | cache[y * layout.width() + x] << bit
+                    })
+                })
+                .collect();
+
+            assert_eq!(sent, original, "row {}", row);
+        }
+    }
+}
@@ -0,0 +1,4 @@
+pub mod layout;
+pub mod ticker;
+#[cfg(feature = "esp")]
+pub mod matrix;
@@ -1,10 +1,16 @@
+use std::time::{Duration, Instant};
+
 use anyhow::anyhow;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::{DrawTarget, Pixel, Point};
 use esp_idf_hal::delay::FreeRtos;
 use esp_idf_hal::prelude::Peripherals;
 
-use crate::matrix::Matrix;
+use matrix::matrix::Matrix;
+use matrix::ticker::{ScrollDirection, Ticker};
 
-mod matrix;
+// Scrolls a greeting across the displays before the blocks start falling
+const TICKER_ENABLED: bool = false;
 
 struct Tetrimino {
     width: usize,
@@ -158,20 +164,41 @@ fn main() -> anyhow::Result<()> {
     let mut display: Matrix<'_, _, 128, 2> = Matrix::new(peripherals.spi2, sck, mosi, cs)?;
     display.initialize()?;
 
+    // The displays are stacked, so the characters come up one per display
+    let mut ticker = Ticker::new(ScrollDirection::Up, Duration::from_millis(60))
+        .with_pause(Duration::from_millis(500));
+
+    if TICKER_ENABLED {
+        ticker.push("HELLO");
+        ticker.push("8x8");
+    }
+
     let mut block = Tetrimino::new_random();
 
     loop {
-        display.fill();
-
-        for width in 0..block.width {
-            for height in 0..block.height {
-                display.set(
-                    (block.position.y + height) * 8 + (block.position.x.saturating_sub(width)),
-                    block.shape[height * block.width + width],
-                );
+        if !ticker.is_idle() {
+            if ticker.update(&mut display, Instant::now())? {
+                display.flush()?;
             }
+
+            FreeRtos::delay_ms(10);
+            continue;
         }
 
+        display.fill();
+
+        let pixels = (0..block.width)
+            .flat_map(|width| (0..block.height).map(move |height| (width, height)))
+            .filter(|(width, height)| block.shape[height * block.width + width] == 1)
+            .map(|(width, height)| {
+                let x = block.position.x.saturating_sub(width) as i32;
+                let y = (block.position.y + height) as i32;
+
+                Pixel(Point::new(x, y), BinaryColor::On)
+            });
+
+        display.draw_iter(pixels)?;
+
         block.position.y += 1;
 
         if block.position.y > 16 {
@@ -1,3 +1,7 @@
+use std::convert::Infallible;
+
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Pixel, Size};
 use esp_idf_hal::gpio::{AnyIOPin, AnyOutputPin, InputPin, Output, OutputPin, PinDriver};
 use esp_idf_hal::peripheral::Peripheral;
 use esp_idf_hal::prelude::*;
@@ -5,6 +9,8 @@ use esp_idf_hal::spi::{SpiAnyPins, SpiConfig, SpiDeviceDriver, SpiDriver};
 use esp_idf_hal::spi::config::DriverConfig;
 use esp_idf_sys::EspError;
 
+use crate::layout::Layout;
+
 #[derive(Copy, Clone)]
 enum RegisterAddressMap {
     Noop = 0x0,
@@ -99,11 +105,17 @@ impl Into<u8> for Mode {
 pub struct Matrix<'d, CS: OutputPin, const BUFFER_SIZE: usize, const DISPLAY_COUNT: usize> {
     spi: SpiDeviceDriver<'d, SpiDriver<'d>>,
     cs: PinDriver<'d, CS, Output>,
+    layout: Layout<DISPLAY_COUNT>,
+    // Pixels row after row across the whole layout
     cache: [u8; BUFFER_SIZE],
     is_dirty: bool,
 }
 
 impl<'d, CS: OutputPin, const BUFFER_SIZE: usize, const DISPLAY_COUNT: usize> Matrix<'d, CS, BUFFER_SIZE, DISPLAY_COUNT> {
+    // Evaluated when `new` is compiled, so a wrong size fails the build
+    const BUFFER_SIZE_CHECK: () = assert!(BUFFER_SIZE == 8 * 8 * DISPLAY_COUNT, "buffer size must be 8 * 8 * display count");
+
+    /// The displays are stacked in one column until another layout is given with `with_layout`.
     pub fn new(
         spi: impl Peripheral<P=impl SpiAnyPins> + 'd,
         sck: impl Peripheral<P=impl InputPin + OutputPin> + 'd,
@@ -118,9 +130,16 @@ impl<'d, CS: OutputPin, const BUFFER_SIZE: usize, const DISPLAY_COUNT: usize> Ma
 
         let mut cs = PinDriver::output(cs)?;
 
-        assert_eq!(BUFFER_SIZE, 8 * 8 * DISPLAY_COUNT, "buffer size must be 8 * 8 * display count");
+        let () = Self::BUFFER_SIZE_CHECK;
+
+        Ok(Self { spi, cs, layout: Layout::column(), cache: [0u8; BUFFER_SIZE], is_dirty: false })
+    }
 
-        Ok(Self { spi, cs, cache: [0u8; BUFFER_SIZE], is_dirty: false })
+    /// Sets how the displays are arranged, which also decides the width and height. Clears the picture.
+    pub fn with_layout(mut self, layout: Layout<DISPLAY_COUNT>) -> Self {
+        self.layout = layout;
+        self.fill();
+        self
     }
 
     pub fn initialize(&mut self) -> Result<(), EspError> {
@@ -161,20 +180,22 @@ impl<'d, CS: OutputPin, const BUFFER_SIZE: usize, const DISPLAY_COUNT: usize> Ma
             return Ok(());
         }
 
+        let width = self.layout.width();
+
         for row in 0..8u8 {
             let mut buffer = [0u8; DISPLAY_COUNT];
 
-            for column in 0..8u8 {
-                let index = (column * 8 + row) as usize;
-
-                for display in 0..DISPLAY_COUNT {
-                    buffer[display] |= self.cache[(display * 8 * 8) + index] << (7 - column);
+            for display in 0..DISPLAY_COUNT {
+                for bit in 0..8u8 {
+                    let (x, y) = self.layout.locate(display, row, bit);
+                    buffer[display] |= self.cache[y * width + x] << bit;
                 }
             }
 
             self.cs.set_low()?;
 
-            for display in 0..DISPLAY_COUNT {
+            // Data is shifted along the chain, so the display furthest from the ESP32 goes first
+            for display in (0..DISPLAY_COUNT).rev() {
                 self.spi.write(&[row + 1, buffer[display]])?;
             }
 
@@ -195,4 +216,43 @@ impl<'d, CS: OutputPin, const BUFFER_SIZE: usize, const DISPLAY_COUNT: usize> Ma
 
         self.cs.set_high()
     }
-}
\ No newline at end of file
+}
+
+// Draws into the cache with the size of the layout, call flush to show it
+impl<'d, CS: OutputPin, const BUFFER_SIZE: usize, const DISPLAY_COUNT: usize> DrawTarget for Matrix<'d, CS, BUFFER_SIZE, DISPLAY_COUNT> {
+    type Color = BinaryColor;
+    type Error = Infallible;
+
+    fn draw_iter<I>(&mut self, pixels: I) -> Result<(), Self::Error>
+    where
+        I: IntoIterator<Item=Pixel<Self::Color>>,
+    {
+        let size = self.size();
+
+        for Pixel(point, color) in pixels {
+            // Anything outside the display is dropped
+            if let Ok((x, y)) = <(u32, u32)>::try_from(point) {
+                if x < size.width && y < size.height {
+                    self.cache[(y * size.width + x) as usize] = color.is_on() as u8;
+                }
+            }
+        }
+
+        self.is_dirty = true;
+
+        Ok(())
+    }
+
+    fn clear(&mut self, color: Self::Color) -> Result<(), Self::Error> {
+        self.cache.fill(color.is_on() as u8);
+        self.is_dirty = true;
+
+        Ok(())
+    }
+}
+
+impl<'d, CS: OutputPin, const BUFFER_SIZE: usize, const DISPLAY_COUNT: usize> OriginDimensions for Matrix<'d, CS, BUFFER_SIZE, DISPLAY_COUNT> {
+    fn size(&self) -> Size {
+        Size::new(self.layout.width() as u32, self.layout.height() as u32)
+    }
+}
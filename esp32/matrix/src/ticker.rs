@@ -0,0 +1,320 @@
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+
+use embedded_graphics::mono_font::{MonoFont, MonoTextStyle};
+use embedded_graphics::mono_font::ascii::FONT_5X7;
+use embedded_graphics::pixelcolor::BinaryColor;
+use embedded_graphics::prelude::{DrawTarget, OriginDimensions, Point};
+use embedded_graphics::text::{Baseline, Text};
+use embedded_graphics::Drawable;
+
+// Pixels between two characters stacked on top of each other
+const LINE_SPACING: u32 = 1;
+
+#[derive(Copy, Clone, PartialEq)]
+pub enum ScrollDirection {
+    // The text is written on one line and moves from right to left
+    Left,
+    // The characters are stacked below each other and move from the bottom to the top
+    Up,
+}
+
+/// Scrolls queued messages across the display one after the other, like a news ticker.
+/// Each message comes in from one side and leaves from the other before the next one starts.
+pub struct Ticker {
+    font: &'static MonoFont<'static>,
+    direction: ScrollDirection,
+    // How long the text stays in place before moving by one pixel
+    step: Duration,
+    // Blank time between two messages
+    pause: Duration,
+    // Messages are queued again once shown
+    looping: bool,
+    queue: VecDeque<String>,
+    current: Option<String>,
+    offset: i32,
+    // When the current message or the pause before it moves on, None when it can right away
+    next: Option<Instant>,
+}
+
+impl Ticker {
+    pub fn new(direction: ScrollDirection, step: Duration) -> Self {
+        Self {
+            font: &FONT_5X7,
+            direction,
+            step,
+            pause: Duration::ZERO,
+            looping: false,
+            queue: VecDeque::new(),
+            current: None,
+            offset: 0,
+            next: None,
+        }
+    }
+
+    /// Any mono font from `embedded-graphics` can be used instead of the 5x7 one.
+    pub fn with_font(mut self, font: &'static MonoFont<'static>) -> Self {
+        self.font = font;
+        self
+    }
+
+    pub fn with_pause(mut self, pause: Duration) -> Self {
+        self.pause = pause;
+        self
+    }
+
+    pub fn with_looping(mut self, looping: bool) -> Self {
+        self.looping = looping;
+        self
+    }
+
+    pub fn set_step(&mut self, step: Duration) {
+        self.step = step;
+    }
+
+    /// Adds a message to the end of the queue.
+    pub fn push(&mut self, text: &str) {
+        self.queue.push_back(text.to_string());
+    }
+
+    /// Drops the current message and everything queued after it.
+    pub fn clear(&mut self) {
+        self.queue.clear();
+        self.current = None;
+    }
+
+    /// True when there is nothing left to show.
+    pub fn is_idle(&self) -> bool {
+        self.current.is_none() && self.queue.is_empty()
+    }
+
+    /// Draws the next frame once the step has passed, meant to be called from the main loop with `Instant::now()`
+    /// followed by a flush. Returns true when the display was drawn to.
+    pub fn update<DISPLAY>(&mut self, display: &mut DISPLAY, now: Instant) -> Result<bool, DISPLAY::Error>
+    where
+        DISPLAY: DrawTarget<Color=BinaryColor> + OriginDimensions,
+    {
+        if self.next.is_some_and(|next| now < next) {
+            return Ok(false);
+        }
+
+        let text = match &self.current {
+            Some(text) => text.clone(),
+            None => match self.queue.pop_front() {
+                Some(text) => {
+                    self.offset = 0;
+                    self.current = Some(text.clone());
+                    text
+                }
+                None => return Ok(false),
+            },
+        };
+
+        display.clear(BinaryColor::Off)?;
+
+        let size = display.size();
+        let style = MonoTextStyle::new(self.font, BinaryColor::On);
+        let character = self.font.character_size;
+
+        // Text starts just outside the display and moves until it has left it on the other side
+        let (distance, start) = match self.direction {
+            ScrollDirection::Left => {
+                let width = text.chars().count() as u32 * (character.width + self.font.character_spacing);
+                (size.width + width, size.width as i32)
+            }
+            ScrollDirection::Up => {
+                let height = text.chars().count() as u32 * (character.height + LINE_SPACING);
+                (size.height + height, size.height as i32)
+            }
+        };
+
+        let position = start - self.offset;
+
+        match self.direction {
+            ScrollDirection::Left => {
+                Text::with_baseline(&text, Point::new(position, 0), style, Baseline::Top).draw(display)?;
+            }
+            ScrollDirection::Up => {
+                // Centered across the width
+                let x = (size.width as i32 - character.width as i32) / 2;
+
+                for (index, char) in text.chars().enumerate() {
+                    let y = position + index as i32 * (character.height + LINE_SPACING) as i32;
+
+                    // Characters outside the display are skipped, the display would drop them anyway
+                    if y + (character.height as i32) < 0 || y >= size.height as i32 {
+                        continue;
+                    }
+
+                    let mut buffer = [0u8; 4];
+                    Text::with_baseline(char.encode_utf8(&mut buffer), Point::new(x, y), style, Baseline::Top).draw(display)?;
+                }
+            }
+        }
+
+        self.offset += 1;
+        self.next = Some(now + self.step);
+
+        if self.offset as u32 > distance {
+            self.current = None;
+            self.next = Some(now + self.pause);
+
+            if self.looping {
+                self.queue.push_back(text);
+            }
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use embedded_graphics::mock_display::MockDisplay;
+    use embedded_graphics::mono_font::ascii::FONT_5X7;
+    use embedded_graphics::mono_font::MonoTextStyle;
+    use embedded_graphics::pixelcolor::BinaryColor;
+    use embedded_graphics::prelude::{Point, Size};
+    use embedded_graphics::primitives::{PointsIter, Rectangle};
+    use embedded_graphics::text::{Baseline, Text};
+    use embedded_graphics::Drawable;
+
+    use crate::ticker::{ScrollDirection, Ticker};
+
+    const STEP: Duration = Duration::from_millis(50);
+    const PAUSE: Duration = Duration::from_millis(500);
+    // The mock display is 64 pixels wide and tall, a 5x7 character is 5 pixels wide
+    const SIZE: i32 = 64;
+    // Frames to bring a single character in and out again, one per pixel moved
+    const LEFT_FRAMES: u32 = SIZE as u32 + 5 + 1;
+
+    fn display() -> MockDisplay<BinaryColor> {
+        let mut display = MockDisplay::new();
+        // Every frame clears the display and the text starts outside of it
+        display.set_allow_overdraw(true);
+        display.set_allow_out_of_bounds_drawing(true);
+        display
+    }
+
+    fn lit(display: &MockDisplay<BinaryColor>) -> Vec<Point> {
+        Rectangle::new(Point::zero(), Size::new_equal(SIZE as u32))
+            .points()
+            .filter(|point| display.get_pixel(*point) == Some(BinaryColor::On))
+            .collect()
+    }
+
+    // Pixels of the text drawn straight at the position
+    fn text(text: &str, position: Point) -> Vec<Point> {
+        let mut display = display();
+        let style = MonoTextStyle::new(&FONT_5X7, BinaryColor::On);
+
+        Text::with_baseline(text, position, style, Baseline::Top).draw(&mut display).unwrap();
+
+        lit(&display)
+    }
+
+    // Runs the frames one step apart, returning the time of the last one
+    fn run(ticker: &mut Ticker, display: &mut MockDisplay<BinaryColor>, start: Instant, frames: u32) -> Instant {
+        let mut now = start;
+
+        for frame in 0..frames {
+            now = start + STEP * frame;
+            assert!(ticker.update(display, now).unwrap(), "frame {} wasn't drawn", frame);
+        }
+
+        now
+    }
+
+    #[test]
+    fn idle_without_messages() {
+        let mut ticker = Ticker::new(ScrollDirection::Left, STEP);
+        let mut display = display();
+
+        assert!(ticker.is_idle());
+        assert!(!ticker.update(&mut display, Instant::now()).unwrap());
+        assert_eq!(display.affected_area().size.width, 0);
+    }
+
+    #[test]
+    fn waits_for_the_step() {
+        let mut ticker = Ticker::new(ScrollDirection::Left, STEP);
+        let mut display = display();
+        let start = Instant::now();
+
+        ticker.push("A");
+
+        assert!(ticker.update(&mut display, start).unwrap());
+        assert!(!ticker.update(&mut display, start + STEP / 2).unwrap());
+        assert!(ticker.update(&mut display, start + STEP).unwrap());
+        assert!(!ticker.is_idle());
+    }
+
+    #[test]
+    fn scrolls_left_across_the_display() {
+        let mut ticker = Ticker::new(ScrollDirection::Left, STEP);
+        let mut display = display();
+        let start = Instant::now();
+
+        ticker.push("A");
+
+        // The first frame has the text just outside the right edge
+        run(&mut ticker, &mut display, start, 1);
+        assert!(lit(&display).is_empty());
+
+        let now = run(&mut ticker, &mut display, start + STEP, SIZE as u32);
+        assert_eq!(lit(&display), text("A", Point::zero()));
+
+        // Then it leaves on the left before the message is done
+        run(&mut ticker, &mut display, now + STEP, LEFT_FRAMES - SIZE as u32 - 1);
+        assert!(lit(&display).is_empty());
+        assert!(ticker.is_idle());
+    }
+
+    #[test]
+    fn scrolls_up_centered() {
+        let mut ticker = Ticker::new(ScrollDirection::Up, STEP);
+        let mut display = display();
+
+        ticker.push("A");
+
+        run(&mut ticker, &mut display, Instant::now(), SIZE as u32 + 1);
+        assert_eq!(lit(&display), text("A", Point::new((SIZE - 5) / 2, 0)));
+    }
+
+    #[test]
+    fn shows_the_queue_in_order_with_a_pause() {
+        let mut ticker = Ticker::new(ScrollDirection::Left, STEP).with_pause(PAUSE);
+        let mut display = display();
+        let start = Instant::now();
+
+        ticker.push("A");
+        ticker.push("B");
+
+        let end = run(&mut ticker, &mut display, start, LEFT_FRAMES);
+
+        assert!(!ticker.update(&mut display, end + PAUSE / 2).unwrap());
+
+        run(&mut ticker, &mut display, end + PAUSE, SIZE as u32 + 1);
+        assert_eq!(lit(&display), text("B", Point::zero()));
+    }
+
+    #[test]
+    fn looping_queues_messages_again() {
+        let mut ticker = Ticker::new(ScrollDirection::Left, STEP).with_looping(true);
+        let mut display = display();
+        let start = Instant::now();
+
+        ticker.push("A");
+
+        let end = run(&mut ticker, &mut display, start, LEFT_FRAMES);
+        assert!(!ticker.is_idle());
+
+        run(&mut ticker, &mut display, end + STEP, SIZE as u32 + 1);
+        assert_eq!(lit(&display), text("A", Point::zero()));
+
+        ticker.clear();
+        assert!(ticker.is_idle());
+    }
+}
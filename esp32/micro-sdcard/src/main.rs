@@ -8,6 +8,7 @@ use shared::tiny_display::TinyDisplay;
 use crate::file_list::FileList;
 use crate::micro_sdcard::MicroSdCard;
 use shared::rotary_encoder::{Direction, RotaryEncoder};
+use shared::sound::Sound;
 
 mod file_list;
 mod micro_sdcard;
@@ -32,9 +33,13 @@ fn main() -> anyhow::Result<()> {
     let s2_pin = peripherals.pins.gpio21;
     let key_pin = peripherals.pins.gpio17;
 
+    // For Passive Buzzer
+    let buzzer = peripherals.pins.gpio18;
+
     let display = TinyDisplay::new(peripherals.i2c0, sda, scl)?;
     let mut sdcard = MicroSdCard::new(peripherals.spi2, sck, mosi, miso, cs)?;
     let mut encoder = RotaryEncoder::new(s1_pin, s2_pin, Some(key_pin))?;
+    let sound = Sound::new(peripherals.rmt.channel0, buzzer)?;
 
     let files = sdcard.list_files()?;
 
@@ -48,6 +53,14 @@ fn main() -> anyhow::Result<()> {
             .lock()
             .map_err(|error| anyhow!("unable to acquire lock: {:?}", error))?;
 
+        // A short tick for every scrolled line, and a low beep when the end of the list is reached
+        match direction {
+            Direction::Clockwise if locked.can_scroll_up() => sound.beep(3000, 5)?,
+            Direction::Anticlockwise if locked.can_scroll_down() => sound.beep(3000, 5)?,
+            Direction::Clockwise | Direction::Anticlockwise => sound.beep(200, 60)?,
+            Direction::None => {}
+        }
+
         match direction {
             Direction::Clockwise => locked.scroll_up(),
             Direction::Anticlockwise => locked.scroll_down(),
@@ -1,5 +1,10 @@
 // Necessary because of this issue: https://github.com/rust-lang/cargo/issues/9641
 fn main() -> Result<(), Box<dyn std::error::Error>> {
+    // The song renderer is built for the host without ESP-IDF
+    if std::env::var("CARGO_FEATURE_ESP").is_err() {
+        return Ok(());
+    }
+
     embuild::build::CfgArgs::output_propagated("ESP_IDF")?;
     embuild::build::LinkArgs::output_propagated("ESP_IDF")?;
     Ok(())
@@ -0,0 +1,49 @@
+use std::fs::File;
+use std::io::BufWriter;
+
+use anyhow::anyhow;
+
+use passive_buzzer::render::{DEFAULT_SAMPLE_RATE, render, write_wav};
+use passive_buzzer::song::Parameters;
+use passive_buzzer::songs;
+
+// Usage: render-song <song> <output.wav> [sample rate]
+fn main() -> anyhow::Result<()> {
+    let arguments: Vec<String> = std::env::args().skip(1).collect();
+
+    let names = songs::all()
+        .iter()
+        .map(|(name, _)| *name)
+        .collect::<Vec<&str>>()
+        .join(", ");
+
+    let (name, output) = match (arguments.first(), arguments.get(1)) {
+        (Some(name), Some(output)) => (name, output),
+        _ => return Err(anyhow!("usage: render-song <song> <output.wav> [sample rate]\navailable songs: {}", names)),
+    };
+
+    let sample_rate = match arguments.get(2) {
+        Some(value) => value.parse().map_err(|_| anyhow!("invalid sample rate: {}", value))?,
+        None => DEFAULT_SAMPLE_RATE,
+    };
+
+    let (_, song) = songs::all()
+        .into_iter()
+        .find(|(song, _)| *song == name.as_str())
+        .ok_or(anyhow!("unknown song: {}, available songs: {}", name, names))?;
+
+    let samples = render(song().as_ref(), &Parameters::default(), sample_rate);
+
+    let mut writer = BufWriter::new(File::create(output)?);
+    write_wav(&mut writer, &samples, sample_rate)?;
+
+    println!(
+        "{}: {} notes, {:.3} seconds at {} Hz",
+        name,
+        song().notes().len(),
+        samples.len() as f32 / sample_rate as f32,
+        sample_rate,
+    );
+
+    Ok(())
+}
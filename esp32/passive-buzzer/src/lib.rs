@@ -0,0 +1,6 @@
+pub mod song;
+pub mod notation;
+pub mod songs;
+pub mod render;
+#[cfg(feature = "esp")]
+pub mod player;
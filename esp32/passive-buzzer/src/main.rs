@@ -1,5 +1,3 @@
-use std::fmt::Display;
-
 use button_driver::{Button, ButtonConfig};
 use esp_idf_hal::delay::FreeRtos;
 use esp_idf_hal::gpio::PinDriver;
@@ -8,11 +6,26 @@ use esp_idf_hal::rmt::*;
 use esp_idf_hal::rmt::config::{Loop, TransmitConfig};
 use rotary_encoder_embedded::{Direction, RotaryEncoder};
 
-use crate::player::Player;
-
-mod songs;
-pub mod song;
-mod player;
+use passive_buzzer::player::{PlaybackMode, Player};
+use passive_buzzer::song::RMT_CLOCK_DIVIDER;
+use shared::segment_animation::{DEFAULT_MARQUEE_STEP_MS, SegmentAnimator};
+use shared::nvs_storage::NvsStorage;
+use shared::segment_display::SegmentDisplay;
+use shared::settings::{Key, Settings};
+use shared::seven_segment::{format_number, glyph};
+
+const VOLUME_STEP: u8 = 5;
+
+// Bump when the stored settings change shape
+const SETTINGS_VERSION: u32 = 1;
+const TRACK: Key<u32> = Key::new("track", 0);
+const VOLUME: Key<u8> = Key::new("volume", 100);
+
+#[derive(PartialEq, Debug, Copy, Clone)]
+enum Mode {
+    Track,
+    Volume,
+}
 
 fn main() -> anyhow::Result<()> {
     esp_idf_sys::link_patches();
@@ -32,7 +45,7 @@ fn main() -> anyhow::Result<()> {
     let buzzer_pin = peripherals.pins.gpio2;
 
     let channel = peripherals.rmt.channel0;
-    let config = TransmitConfig::new().looping(Loop::Endless);
+    let config = TransmitConfig::new().clock_divider(RMT_CLOCK_DIVIDER).looping(Loop::Endless);
     let transmitter: TxRmtDriver<'static> = TxRmtDriver::new(channel, buzzer_pin, &config)?;
 
     // Configure rotary encoder
@@ -43,68 +56,94 @@ fn main() -> anyhow::Result<()> {
     let mut button = Button::new(key, ButtonConfig::default());
     let mut encoder = RotaryEncoder::new(rotary_dt, rotary_clk).into_standard_mode();
 
-    // Setup display
-    let mut dio = PinDriver::input_output(dio_pin)?;
-    let mut clock = PinDriver::input_output(clock_pin)?;
-
-    let mut delay = FreeRtos;
-    let mut display = tm1637::TM1637::new(&mut clock, &mut dio, &mut delay);
-
-    display.init().unwrap();
-    display.clear().unwrap();
-    display.set_brightness(5).unwrap();
+    // Setup display, animations run on their own thread
+    let display = SegmentAnimator::new(SegmentDisplay::new(clock_pin, dio_pin)?, 5);
 
     // Setup player
     let mut player = Player::new(transmitter);
+    player.set_playback_mode(PlaybackMode::RepeatAll);
+
+    // Restore the track and volume from the last run
+    let mut settings = Settings::open(NvsStorage::new()?, "passive-buzzer", SETTINGS_VERSION)?;
+
+    player.current_track = settings.get(&TRACK)? as usize % player.track_count();
+    player.set_volume(settings.get(&VOLUME)?)?;
+
+    let mut previous_mode = Mode::Track;
 
     loop {
         button.tick();
         encoder.update();
 
+        // Switch to volume mode while the button is held
+        let current_mode = match button.raw_state().is_held() {
+            true => Mode::Volume,
+            false => Mode::Track,
+        };
+
+        if current_mode != previous_mode {
+            match current_mode {
+                Mode::Volume => display.show(format_number(player.volume())?)?,
+                Mode::Track if player.is_playing() => show_track(&display, &player)?,
+                Mode::Track => display.clear()?,
+            }
+
+            previous_mode = current_mode;
+        }
+
         // When the button is pressed, stop the player and clear the display.
         if button.is_clicked() {
             player.stop()?;
-            display.clear().unwrap();
+            display.clear()?;
         }
 
-        match encoder.direction() {
-            Direction::Clockwise => {
+        match (encoder.direction(), current_mode) {
+            (Direction::Clockwise, Mode::Track) => {
                 player.previous()?;
-                display.print_raw(0, &get_digits(player.current_track + 1).as_slice()).unwrap();
+                show_track(&display, &player)?;
             }
-            Direction::Anticlockwise => {
+            (Direction::Anticlockwise, Mode::Track) => {
                 player.next()?;
-                display.print_raw(0, &get_digits(player.current_track + 1).as_slice()).unwrap();
+                show_track(&display, &player)?;
+            }
+            (Direction::Clockwise, Mode::Volume) => {
+                player.set_volume(player.volume().saturating_sub(VOLUME_STEP))?;
+                display.show(format_number(player.volume())?)?;
+            }
+            (Direction::Anticlockwise, Mode::Volume) => {
+                player.set_volume(player.volume().saturating_add(VOLUME_STEP))?;
+                display.show(format_number(player.volume())?)?;
             }
-            Direction::None => {
+            (Direction::None, _) => {
                 // Do nothing
             }
         }
 
+        // When a song ends, the player moves on according to its playback mode.
+        if player.update()? && current_mode == Mode::Track {
+            match player.is_playing() {
+                true => show_track(&display, &player)?,
+                false => display.clear()?,
+            }
+        }
+
+        // Changes are only written to the flash once they settle
+        settings.set(&TRACK, player.current_track as u32)?;
+        settings.set(&VOLUME, player.volume())?;
+        settings.update()?;
+
         button.reset();
 
         FreeRtos::delay_ms(1);
     }
 }
 
-const DIGITS: [u8; 16] = [
-    0x3f, 0x06, 0x5b, 0x4f,
-    0x66, 0x6d, 0x7d, 0x07,
-    0x7f, 0x6f, 0x77, 0x7c,
-    0x39, 0x5e, 0x79, 0x71,
-];
-
-fn get_digits<Value>(value: Value) -> Vec<u8> where Value: Display {
-    let mut numbers: Vec<u8> = value
-        .to_string()
+// Scrolls the track number and name, letters that have no seven-segment glyph are left blank
+fn show_track(display: &SegmentAnimator, player: &Player) -> anyhow::Result<()> {
+    let title: String = format!("{} {}", player.current_track + 1, player.track_name())
         .chars()
-        .map(|d| d.to_digit(10).unwrap())
-        .map(|d| DIGITS[(d & 0xf) as usize])
-        .rev()
+        .map(|char| if glyph(char).is_some() { char } else { ' ' })
         .collect();
 
-    numbers.resize(4, 0x00);
-    numbers.reverse();
-
-    numbers
-}
\ No newline at end of file
+    display.marquee(&title, DEFAULT_MARQUEE_STEP_MS)
+}
@@ -0,0 +1,420 @@
+//! Compact text notation for songs, parsed at compile time by the [`song!`](crate::song!) macro.
+//!
+//! Notes are written as `<name><octave>/<duration>`, separated by whitespace:
+//!
+//! - `E5/4` is a quarter E5, `C#4/8` an eighth C sharp and `Bb3/2` a half B flat.
+//! - `R/4` is a quarter rest.
+//! - Durations are 1, 2, 4, 8, 16, 32 or 64, followed by `.` for a dotted note (`B4/4.`).
+//! - A `~` right after a note ties it to the next one, which must have the same pitch (`A4/2~ A4/8`).
+//! - `[:` and `:]` repeat the section between them twice, `:]3` repeats it three times.
+//! - `|` can be used as a bar line and `//` starts a comment until the end of the line, both are ignored.
+//!
+//! Any mistake in the notation fails the build.
+
+use crate::song::{HIGHEST_NOTE, LOWEST_NOTE, Note, REST};
+
+// Upper bound of notes in a single song, used while counting them
+pub const MAX_NOTES: usize = 1024;
+
+pub struct Notation<const N: usize> {
+    pub notes: [(Note, i8); N],
+    // Whether each note is tied to the one after it
+    pub ties: [bool; N],
+    pub length: usize,
+}
+
+/// Counts the notes of a song once its repeats are expanded.
+pub const fn count(text: &str) -> usize {
+    parse::<MAX_NOTES>(text).length
+}
+
+pub const fn parse<const N: usize>(text: &str) -> Notation<N> {
+    let bytes = text.as_bytes();
+
+    let mut notation = Notation { notes: [(REST, 4); N], ties: [false; N], length: 0 };
+    let mut position = 0;
+
+    // Where the current repeat section starts and how many times it has been played so far
+    let mut repeat_start: Option<usize> = None;
+    let mut repeat_pass = 1;
+
+    loop {
+        let (start, end) = next_token(bytes, position);
+
+        if start == end {
+            break;
+        }
+
+        position = end;
+
+        if bytes[start] == b'|' && end - start == 1 {
+            continue;
+        }
+
+        if bytes[start] == b'[' {
+            if end - start != 2 || bytes[start + 1] != b':' {
+                panic!("invalid repeat start in song notation, expected `[:`");
+            }
+
+            if repeat_start.is_some() {
+                panic!("nested repeats are not supported in song notation");
+            }
+
+            repeat_start = Some(end);
+            continue;
+        }
+
+        if bytes[start] == b':' {
+            if end - start < 2 || bytes[start + 1] != b']' {
+                panic!("invalid repeat end in song notation, expected `:]`");
+            }
+
+            let times = match end - start {
+                2 => 2,
+                _ => parse_number(bytes, start + 2, end),
+            };
+
+            if times < 2 {
+                panic!("a repeat must be played at least twice");
+            }
+
+            match repeat_start {
+                Some(section) if repeat_pass < times => {
+                    repeat_pass += 1;
+                    position = section;
+                }
+                Some(_) => {
+                    repeat_start = None;
+                    repeat_pass = 1;
+                }
+                None => panic!("repeat end without a matching start in song notation"),
+            }
+
+            continue;
+        }
+
+        let (note, divider, tied) = parse_note(bytes, start, end);
+
+        if notation.length > 0 && notation.ties[notation.length - 1] && !same_pitch(notation.notes[notation.length - 1].0, note) {
+            panic!("tied notes must have the same pitch");
+        }
+
+        if notation.length == N {
+            panic!("song has too many notes");
+        }
+
+        notation.notes[notation.length] = (note, divider);
+        notation.ties[notation.length] = tied;
+        notation.length += 1;
+    }
+
+    if repeat_start.is_some() {
+        panic!("repeat start without a matching end in song notation");
+    }
+
+    if notation.length > 0 && notation.ties[notation.length - 1] {
+        panic!("the last note of a song cannot be tied");
+    }
+
+    notation
+}
+
+// Returns the bounds of the next whitespace separated token, skipping comments
+const fn next_token(bytes: &[u8], mut position: usize) -> (usize, usize) {
+    loop {
+        while position < bytes.len() && bytes[position].is_ascii_whitespace() {
+            position += 1;
+        }
+
+        if position + 1 < bytes.len() && bytes[position] == b'/' && bytes[position + 1] == b'/' {
+            while position < bytes.len() && bytes[position] != b'\n' {
+                position += 1;
+            }
+
+            continue;
+        }
+
+        break;
+    }
+
+    let start = position;
+
+    while position < bytes.len() && !bytes[position].is_ascii_whitespace() {
+        position += 1;
+    }
+
+    (start, position)
+}
+
+const fn parse_note(bytes: &[u8], start: usize, end: usize) -> (Note, i8, bool) {
+    let mut index = start + 1;
+
+    let note = match bytes[start] {
+        b'R' => REST,
+        letter => {
+            let mut semitone: i32 = match letter {
+                b'C' => 0,
+                b'D' => 2,
+                b'E' => 4,
+                b'F' => 5,
+                b'G' => 7,
+                b'A' => 9,
+                b'B' => 11,
+                _ => panic!("unknown note name in song notation"),
+            };
+
+            if index < end && bytes[index] == b'#' {
+                semitone += 1;
+                index += 1;
+            } else if index < end && bytes[index] == b'b' {
+                semitone -= 1;
+                index += 1;
+            }
+
+            if index >= end || !bytes[index].is_ascii_digit() {
+                panic!("missing octave in song notation");
+            }
+
+            let octave = (bytes[index] - b'0') as i32;
+            index += 1;
+
+            // MIDI numbers start at C-1, so C4 is 60
+            let number = (octave + 1) * 12 + semitone;
+
+            if number < midi(LOWEST_NOTE) || number > midi(HIGHEST_NOTE) {
+                panic!("note is out of range in song notation, notes go from B0 to D#8");
+            }
+
+            Note::Pitch(number as u8)
+        }
+    };
+
+    if index >= end || bytes[index] != b'/' {
+        panic!("missing duration in song notation, expected `/` after the note");
+    }
+
+    let mut duration_end = index + 1;
+
+    while duration_end < end && bytes[duration_end].is_ascii_digit() {
+        duration_end += 1;
+    }
+
+    let divider = match parse_number(bytes, index + 1, duration_end) {
+        value @ (1 | 2 | 4 | 8 | 16 | 32 | 64) => value as i8,
+        _ => panic!("invalid duration in song notation, expected 1, 2, 4, 8, 16, 32 or 64"),
+    };
+
+    index = duration_end;
+
+    // Negative dividers are dotted notes
+    let mut dotted = false;
+
+    if index < end && bytes[index] == b'.' {
+        dotted = true;
+        index += 1;
+    }
+
+    let mut tied = false;
+
+    if index < end && bytes[index] == b'~' {
+        tied = true;
+        index += 1;
+    }
+
+    if index != end {
+        panic!("unexpected character in song notation");
+    }
+
+    (note, if dotted { -divider } else { divider }, tied)
+}
+
+// PartialEq can't be used in const functions
+const fn same_pitch(first: Note, second: Note) -> bool {
+    match (first.midi(), second.midi()) {
+        (Some(first), Some(second)) => first == second,
+        (None, None) => true,
+        _ => false,
+    }
+}
+
+const fn midi(note: Note) -> i32 {
+    match note.midi() {
+        Some(number) => number as i32,
+        None => panic!("rests have no pitch"),
+    }
+}
+
+const fn parse_number(bytes: &[u8], start: usize, end: usize) -> usize {
+    if start == end {
+        panic!("missing number in song notation");
+    }
+
+    let mut value = 0;
+    let mut index = start;
+
+    while index < end {
+        if !bytes[index].is_ascii_digit() {
+            panic!("invalid number in song notation");
+        }
+
+        value = value * 10 + (bytes[index] - b'0') as usize;
+        index += 1;
+    }
+
+    value
+}
+
+/// Declares a song from its text notation, see the [`notation`](crate::notation) module for the syntax.
+///
+/// ```ignore
+/// song! {
+///     pub struct Scale {
+///         tempo: 120,
+///         notes: "C4/4 D4/4 E4/4 F4/4 | G4/2. R/4",
+///     }
+/// }
+/// ```
+#[macro_export]
+macro_rules! song {
+    ($(#[$meta:meta])* $visibility:vis struct $name:ident { tempo: $tempo:expr, notes: $notes:expr $(,)? }) => {
+        $(#[$meta])*
+        $visibility struct $name {}
+
+        impl $name {
+            pub fn new() -> Self {
+                $name {}
+            }
+        }
+
+        impl Default for $name {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl $crate::song::Song for $name {
+            fn tempo(&self) -> f32 {
+                $tempo as f32
+            }
+
+            fn notes(&self) -> &[($crate::song::Note, i8)] {
+                const LENGTH: usize = $crate::notation::count($notes);
+                const NOTES: [($crate::song::Note, i8); LENGTH] = $crate::notation::parse::<LENGTH>($notes).notes;
+
+                &NOTES
+            }
+
+            fn ties(&self) -> &[bool] {
+                const LENGTH: usize = $crate::notation::count($notes);
+                const TIES: [bool; LENGTH] = $crate::notation::parse::<LENGTH>($notes).ties;
+
+                &TIES
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::notation::{count, parse, Notation};
+    use crate::song::{A4, AS4, B0, C4, CS4, DS8, E5, Note, REST};
+    use crate::songs;
+    use crate::songs::original;
+
+    fn notes<const N: usize>(notation: &Notation<N>) -> Vec<(Note, i8)> {
+        notation.notes[..notation.length].to_vec()
+    }
+
+    #[test]
+    fn reads_octaves_sharps_and_flats() {
+        let notation = parse::<6>("C4/4 C#4/8 Db4/8 Bb4/2 B0/1 D#8/64");
+
+        assert_eq!(notes(&notation), [(C4, 4), (CS4, 8), (CS4, 8), (AS4, 2), (B0, 1), (DS8, 64)]);
+    }
+
+    #[test]
+    fn reads_rests_dots_and_ties() {
+        let notation = parse::<4>("R/4. A4/2~ A4/8 E5/16.");
+
+        assert_eq!(notes(&notation), [(REST, -4), (A4, 2), (A4, 8), (E5, -16)]);
+        assert_eq!(notation.ties, [false, true, false, false]);
+    }
+
+    #[test]
+    fn skips_bar_lines_and_comments() {
+        let notation = parse::<2>("
+            // the intro
+            A4/4 | E5/4 // the end
+        ");
+
+        assert_eq!(notes(&notation), [(A4, 4), (E5, 4)]);
+    }
+
+    #[test]
+    fn expands_repeats() {
+        let notation = parse::<8>("C4/4 [: A4/8 E5/8 :] [: R/2 :]3");
+
+        assert_eq!(notes(&notation), [(C4, 4), (A4, 8), (E5, 8), (A4, 8), (E5, 8), (REST, 2), (REST, 2), (REST, 2)]);
+        assert_eq!(count("C4/4 [: A4/8 E5/8 :] [: R/2 :]3"), 8);
+        assert_eq!(count("[: A4/4 :]12"), 12);
+    }
+
+    #[test]
+    #[should_panic(expected = "invalid duration in song notation")]
+    fn rejects_invalid_durations() {
+        parse::<1>("A4/3");
+    }
+
+    #[test]
+    #[should_panic(expected = "note is out of range in song notation")]
+    fn rejects_notes_below_the_range() {
+        parse::<1>("Bb0/4");
+    }
+
+    #[test]
+    #[should_panic(expected = "note is out of range in song notation")]
+    fn rejects_notes_above_the_range() {
+        parse::<1>("E8/4");
+    }
+
+    #[test]
+    #[should_panic(expected = "tied notes must have the same pitch")]
+    fn rejects_ties_between_different_notes() {
+        parse::<2>("A4/4~ C4/4");
+    }
+
+    #[test]
+    #[should_panic(expected = "the last note of a song cannot be tied")]
+    fn rejects_a_tie_at_the_end() {
+        parse::<1>("A4/4~");
+    }
+
+    #[test]
+    #[should_panic(expected = "a repeat must be played at least twice")]
+    fn rejects_single_repeats() {
+        parse::<1>("[: A4/4 :]1");
+    }
+
+    #[test]
+    #[should_panic(expected = "repeat end without a matching start")]
+    fn rejects_unmatched_repeat_ends() {
+        parse::<1>("A4/4 :]");
+    }
+
+    #[test]
+    fn songs_expand_to_their_original_tables() {
+        let originals = [
+            original::GREEN_HILL,
+            original::THE_LION_SLEEPS_TONIGHT,
+            original::SUPER_MARIO_BROS,
+            original::TETRIS,
+        ];
+
+        for ((name, song), original) in songs::all().into_iter().zip(originals) {
+            let song = song();
+
+            assert_eq!(song.notes(), original, "{}", name);
+            assert!(song.ties().iter().all(|tied| !tied), "{}", name);
+        }
+    }
+}
@@ -1,53 +1,157 @@
 use std::sync::{Arc, mpsc, Mutex};
-use std::sync::mpsc::Sender;
+use std::sync::mpsc::{Receiver, Sender};
 use std::thread::spawn;
 
+use anyhow::anyhow;
 use esp_idf_hal::rmt::TxRmtDriver;
 
-use crate::song::Song;
-use crate::songs::green_hill::GreenHill;
-use crate::songs::super_mario_bros::SuperMarioBros;
-use crate::songs::tetris::Tetris;
-use crate::songs::the_lion_sleeps_tonight::TheLionSleepsTonight;
+use crate::song::{Articulation, MAX_TEMPO, MAX_VOLUME, MIN_TEMPO, Parameters, Playback, Tuning};
+use crate::songs;
+use crate::songs::SongFactory;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum PlaybackMode {
+    // Stop once the current song ends
+    StopAtEnd,
+    // Play the following song, stop after the last one
+    AutoAdvance,
+    // Play the current song over and over
+    RepeatOne,
+    // Play the following song, wrapping around after the last one
+    RepeatAll,
+    // Play a random song other than the current one
+    Shuffle,
+}
 
 pub struct Player {
     pub current_track: usize,
+    playback_mode: PlaybackMode,
     sender: Option<Sender<()>>,
     transmitter: Arc<Mutex<TxRmtDriver<'static>>>,
     is_playing: bool,
-    songs: Vec<fn() -> Box<dyn Song>>,
+    // Shared with the playback thread so they can change in the middle of a song
+    parameters: Arc<Mutex<Parameters>>,
+    songs: Vec<(&'static str, SongFactory)>,
+    // Every call to play starts a new session, so a song that finishes right after
+    // being replaced by another one is not mistaken for the current one.
+    session: usize,
+    // Sent by the playback thread when a song finishes or fails, along with its session
+    finished_sender: Sender<(usize, Playback)>,
+    finished_receiver: Receiver<(usize, Playback)>,
 }
 
 impl Player {
     pub fn new(tx: TxRmtDriver<'static>) -> Self {
+        let (finished_sender, finished_receiver) = mpsc::channel();
+
         Self {
             transmitter: Arc::new(Mutex::new(tx)),
             current_track: 0,
+            playback_mode: PlaybackMode::StopAtEnd,
             is_playing: false,
+            parameters: Arc::new(Mutex::new(Parameters::default())),
             sender: None,
-            songs: vec![
-                || Box::new(GreenHill::new()),
-                || Box::new(TheLionSleepsTonight::new()),
-                || Box::new(SuperMarioBros::new()),
-                || Box::new(Tetris::new()),
-            ],
+            session: 0,
+            finished_sender,
+            finished_receiver,
+            songs: songs::all(),
+        }
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.is_playing
+    }
+
+    pub fn track_count(&self) -> usize {
+        self.songs.len()
+    }
+
+    /// Name of the current track, as listed in [`songs::all`].
+    pub fn track_name(&self) -> &'static str {
+        self.songs.get(self.current_track).map(|(name, _)| *name).unwrap_or_default()
+    }
+
+    pub fn playback_mode(&self) -> PlaybackMode {
+        self.playback_mode
+    }
+
+    pub fn set_playback_mode(&mut self, mode: PlaybackMode) {
+        self.playback_mode = mode;
+    }
+
+    pub fn parameters(&self) -> Parameters {
+        match self.parameters.lock() {
+            Ok(parameters) => *parameters,
+            Err(_) => Parameters::default(),
         }
     }
 
+    pub fn volume(&self) -> u8 {
+        self.parameters().volume
+    }
+
+    /// Sets the volume from 0 (silent) to 100 (loudest), higher values are clamped.
+    pub fn set_volume(&mut self, volume: u8) -> anyhow::Result<()> {
+        self.update_parameters(|parameters| parameters.volume = volume.min(MAX_VOLUME))
+    }
+
+    /// Sets the tempo multiplier, 1.0 plays songs as written, clamped between 0.25 and 4.0.
+    pub fn set_tempo(&mut self, tempo: f32) -> anyhow::Result<()> {
+        self.update_parameters(|parameters| parameters.tempo = tempo.clamp(MIN_TEMPO, MAX_TEMPO))
+    }
+
+    /// Shifts every note by the given amount of semitones.
+    pub fn set_transpose(&mut self, semitones: i8) -> anyhow::Result<()> {
+        self.update_parameters(|parameters| parameters.transpose = semitones)
+    }
+
+    pub fn set_articulation(&mut self, articulation: Articulation) -> anyhow::Result<()> {
+        self.update_parameters(|parameters| parameters.articulation = articulation)
+    }
+
+    /// Changes the reference pitch and cents offset every note is computed from,
+    /// clamped between 400 and 480 Hz and -100 and 100 cents.
+    pub fn set_tuning(&mut self, tuning: Tuning) -> anyhow::Result<()> {
+        self.update_parameters(|parameters| parameters.tuning = tuning.clamped())
+    }
+
+    fn update_parameters(&mut self, update: impl FnOnce(&mut Parameters)) -> anyhow::Result<()> {
+        let mut parameters = self.parameters
+            .lock()
+            .map_err(|error| anyhow!("unable to acquire lock: {:?}", error))?;
+
+        update(&mut parameters);
+
+        Ok(())
+    }
+
     pub fn play(&mut self) -> anyhow::Result<()> {
         self.is_playing = true;
+        self.session = self.session.wrapping_add(1);
 
+        let session = self.session;
+        let finished = self.finished_sender.clone();
+        let parameters = self.parameters.clone();
         let transmitter = self.transmitter.clone();
         let (sender, receiver) = mpsc::channel();
         let song = self.songs.get_mut(self.current_track);
 
-        if let Some(result) = song {
-            let song = result.clone();
+        if let Some((_, result)) = song {
+            let song = *result;
             self.sender = Some(sender);
 
             spawn(move || {
-                if let Ok(mut transmitter) = transmitter.lock() {
-                    song().play(&mut transmitter, receiver).unwrap();
+                let playback = match transmitter.lock() {
+                    Ok(mut transmitter) => song().play(&mut transmitter, receiver, &parameters).unwrap_or_else(|error| {
+                        println!("failed to play song: {:?}", error);
+                        Playback::Failed
+                    }),
+                    Err(_) => Playback::Failed,
+                };
+
+                if playback != Playback::Stopped {
+                    // The player may have been dropped in the meantime, nothing to notify then.
+                    finished.send((session, playback)).ok();
                 }
             });
         }
@@ -59,7 +163,8 @@ impl Player {
         self.is_playing = false;
 
         if let Some(sender) = self.sender.take() {
-            sender.send(())?;
+            // The song may have just finished on its own, in which case there is no one left to stop.
+            sender.send(()).ok();
         }
 
         Ok(())
@@ -82,4 +187,63 @@ impl Player {
         self.current_track = self.current_track.wrapping_sub(1) % self.songs.len();
         self.play()
     }
-}
\ No newline at end of file
+
+    /// Picks up the completion signal sent by the playback thread and decides what comes next
+    /// based on the playback mode. Returns true when the current track or the playing state changed.
+    pub fn update(&mut self) -> anyhow::Result<bool> {
+        let mut finished = None;
+
+        while let Ok((session, playback)) = self.finished_receiver.try_recv() {
+            if session == self.session {
+                finished = Some(playback);
+            }
+        }
+
+        let playback = match finished {
+            Some(playback) if self.is_playing => playback,
+            _ => return Ok(false),
+        };
+
+        self.is_playing = false;
+        self.sender = None;
+
+        // A song that failed already reported why, moving on would most likely fail the same way
+        if playback == Playback::Failed {
+            return Ok(true);
+        }
+
+        match self.playback_mode {
+            PlaybackMode::StopAtEnd => {}
+            PlaybackMode::AutoAdvance => {
+                if self.current_track + 1 < self.songs.len() {
+                    self.current_track += 1;
+                    self.play()?;
+                }
+            }
+            PlaybackMode::RepeatOne => {
+                self.play()?;
+            }
+            PlaybackMode::RepeatAll => {
+                self.current_track = (self.current_track + 1) % self.songs.len();
+                self.play()?;
+            }
+            PlaybackMode::Shuffle => {
+                self.current_track = self.random_track();
+                self.play()?;
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn random_track(&self) -> usize {
+        if self.songs.len() < 2 {
+            return self.current_track;
+        }
+
+        // Skip over the current track so shuffle never plays the same song twice in a row
+        let offset = fastrand::usize(1..self.songs.len());
+
+        (self.current_track + offset) % self.songs.len()
+    }
+}
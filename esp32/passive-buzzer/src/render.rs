@@ -0,0 +1,218 @@
+use std::io::{self, Write};
+
+use crate::song::{Parameters, period_ticks, pulse_widths, RMT_COUNTER_CLOCK_HZ, Song};
+
+pub const DEFAULT_SAMPLE_RATE: u32 = 44_100;
+
+const AMPLITUDE: i16 = i16::MAX / 2;
+
+/// Renders the song as 16-bit mono samples, using the same note durations and
+/// pulse widths the buzzer gets when the song is played on the device.
+pub fn render(song: &dyn Song, parameters: &Parameters, sample_rate: u32) -> Vec<i16> {
+    let mut samples = vec![];
+
+    for (index, (note, divider)) in song.notes().iter().enumerate() {
+        let tied = song.ties().get(index).copied().unwrap_or(false);
+        let (note_duration, sounded) = song.timing(*divider, tied, parameters);
+        let frequency = note.transpose(parameters.transpose).frequency(&parameters.tuning);
+
+        // The device can only delay whole milliseconds, so the remainder is dropped here as well
+        let tone = sample_count(sounded as u32, sample_rate);
+
+        if frequency <= 0.0 || parameters.volume == 0 {
+            samples.resize(samples.len() + tone, 0);
+        } else {
+            let (high, low) = pulse_widths(period_ticks(frequency), parameters.volume);
+            let period = high as u64 + low as u64;
+
+            samples.extend((0..tone as u64).map(|index| {
+                let tick = index * RMT_COUNTER_CLOCK_HZ as u64 / sample_rate as u64;
+
+                if tick % period < high as u64 { AMPLITUDE } else { -AMPLITUDE }
+            }));
+        }
+
+        if sounded < note_duration {
+            let gap = sample_count((note_duration - sounded) as u32, sample_rate);
+            samples.resize(samples.len() + gap, 0);
+        }
+    }
+
+    samples
+}
+
+/// Writes the samples as a 16-bit mono PCM WAV file.
+pub fn write_wav<W: Write>(writer: &mut W, samples: &[i16], sample_rate: u32) -> io::Result<()> {
+    let data_size = (samples.len() * 2) as u32;
+
+    writer.write_all(b"RIFF")?;
+    writer.write_all(&(36 + data_size).to_le_bytes())?;
+    writer.write_all(b"WAVE")?;
+
+    // Format chunk: PCM, 1 channel, 16 bits per sample
+    writer.write_all(b"fmt ")?;
+    writer.write_all(&16u32.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&1u16.to_le_bytes())?;
+    writer.write_all(&sample_rate.to_le_bytes())?;
+    writer.write_all(&(sample_rate * 2).to_le_bytes())?;
+    writer.write_all(&2u16.to_le_bytes())?;
+    writer.write_all(&16u16.to_le_bytes())?;
+
+    writer.write_all(b"data")?;
+    writer.write_all(&data_size.to_le_bytes())?;
+
+    for sample in samples {
+        writer.write_all(&sample.to_le_bytes())?;
+    }
+
+    Ok(())
+}
+
+fn sample_count(milliseconds: u32, sample_rate: u32) -> usize {
+    (milliseconds as u64 * sample_rate as u64 / 1000) as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use shared::sound_effect::MAX_PULSE_TICKS;
+
+    use crate::render::{render, write_wav};
+    use crate::song::{Articulation, MAX_REFERENCE, Parameters, Song, Tuning, pulse_widths};
+    use crate::songs;
+    use crate::songs::tetris::Tetris;
+
+    const SAMPLE_RATE: u32 = 8_000;
+
+    // Name, notes, duration in milliseconds and samples at 8 kHz of every song, so a change to a note table shows up here
+    const EXPECTED: [(&str, usize, u32, usize); 4] = [
+        ("green-hill", 175, 69_814, 557_472),
+        ("the-lion-sleeps-tonight", 301, 127_475, 1_018_472),
+        ("super-mario-bros", 321, 101_531, 810_616),
+        ("tetris", 99, 35_437, 282_896),
+    ];
+
+    fn duration(song: &dyn Song, parameters: &Parameters) -> f32 {
+        song.notes()
+            .iter()
+            .enumerate()
+            .map(|(index, (_, divider))| {
+                let tied = song.ties().get(index).copied().unwrap_or(false);
+                song.timing(*divider, tied, parameters).0
+            })
+            .sum()
+    }
+
+    #[test]
+    fn songs_match_their_note_tables() {
+        let songs = songs::all();
+
+        assert_eq!(songs.len(), EXPECTED.len());
+
+        for ((name, song), (expected_name, notes, milliseconds, samples)) in songs.into_iter().zip(EXPECTED) {
+            let song = song();
+            let parameters = Parameters::default();
+
+            assert_eq!(name, expected_name);
+            assert_eq!(song.notes().len(), notes, "{}", name);
+            assert_eq!(duration(song.as_ref(), &parameters) as u32, milliseconds, "{}", name);
+            assert_eq!(render(song.as_ref(), &parameters, SAMPLE_RATE).len(), samples, "{}", name);
+        }
+    }
+
+    #[test]
+    fn normal_plays_like_the_original() {
+        for (name, song) in songs::all() {
+            let song = song();
+            let whole_note = 60000.0 * 4.0 / song.tempo();
+
+            // Before articulations every note sounded for 90% of its written length, followed by the next one right away
+            let expected: usize = song.notes()
+                .iter()
+                .map(|(_, divider)| {
+                    let written = match *divider > 0 {
+                        true => whole_note / *divider as f32,
+                        false => whole_note / divider.abs() as f32 * 1.5,
+                    };
+
+                    (written * 0.9) as u64 * SAMPLE_RATE as u64 / 1000
+                })
+                .sum::<u64>() as usize;
+
+            for (index, (_, divider)) in song.notes().iter().enumerate() {
+                let (duration, sounded) = song.timing(*divider, false, &Parameters::default());
+                assert_eq!(duration, sounded, "{} note {}", name, index);
+            }
+
+            assert_eq!(render(song.as_ref(), &Parameters::default(), SAMPLE_RATE).len(), expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn staccato_keeps_the_pace_and_legato_is_longer() {
+        let song = Tetris::new();
+        let mut parameters = Parameters::default();
+        let normal = render(&song, &parameters, SAMPLE_RATE);
+
+        parameters.articulation = Articulation::Staccato;
+        let staccato = render(&song, &parameters, SAMPLE_RATE);
+
+        // Whole milliseconds are dropped separately for the sound and the gap, so a few samples per note may differ
+        assert!(normal.len().abs_diff(staccato.len()) <= song.notes().len() * SAMPLE_RATE as usize / 1000);
+        assert!(staccato.iter().filter(|sample| **sample == 0).count() > normal.iter().filter(|sample| **sample == 0).count());
+
+        parameters.articulation = Articulation::Legato;
+        assert!((duration(&song, &parameters) * 0.9 - duration(&song, &Parameters::default())).abs() < 1.0);
+    }
+
+    #[test]
+    fn tempo_scales_the_length() {
+        let song = Tetris::new();
+        let mut parameters = Parameters::default();
+        let normal = duration(&song, &parameters);
+
+        parameters.tempo = 2.0;
+
+        assert!((duration(&song, &parameters) * 2.0 - normal).abs() < 1.0);
+    }
+
+    #[test]
+    fn silent_at_zero_volume() {
+        let song = Tetris::new();
+        let parameters = Parameters { volume: 0, ..Parameters::default() };
+        let samples = render(&song, &parameters, SAMPLE_RATE);
+
+        assert_eq!(samples.len(), EXPECTED[3].3);
+        assert!(samples.iter().all(|sample| *sample == 0));
+    }
+
+    #[test]
+    fn writes_a_wav_header() {
+        let mut output = vec![];
+        write_wav(&mut output, &[1, -1, 2], SAMPLE_RATE).unwrap();
+
+        assert_eq!(output.len(), 44 + 6);
+        assert_eq!(&output[0..4], b"RIFF");
+        assert_eq!(&output[8..12], b"WAVE");
+        assert_eq!(u32::from_le_bytes(output[24..28].try_into().unwrap()), SAMPLE_RATE);
+        assert_eq!(u32::from_le_bytes(output[40..44].try_into().unwrap()), 6);
+        assert_eq!(i16::from_le_bytes([output[46], output[47]]), -1);
+    }
+
+    #[test]
+    fn pulses_saturate_to_the_rmt_limit() {
+        assert_eq!(pulse_widths(1_000, 100), (500, 500));
+        assert_eq!(pulse_widths(1_000, 0), (1, 999));
+        assert_eq!(pulse_widths(80_000, 100), (MAX_PULSE_TICKS as u16, MAX_PULSE_TICKS as u16));
+        assert_eq!(pulse_widths(40_000, 10), (2_000, MAX_PULSE_TICKS as u16));
+    }
+
+    #[test]
+    fn tuning_is_clamped() {
+        let tuning = Tuning { reference: 10_000.0, cents: -500.0 }.clamped();
+        assert_eq!((tuning.reference, tuning.cents), (MAX_REFERENCE, -100.0));
+
+        let tuning = Tuning { reference: f32::NAN, cents: f32::INFINITY }.clamped();
+        assert_eq!((tuning.reference, tuning.cents), (440.0, 0.0));
+    }
+}
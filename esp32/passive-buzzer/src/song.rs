@@ -1,152 +1,340 @@
+#[cfg(feature = "esp")]
+use std::sync::Mutex;
+#[cfg(feature = "esp")]
 use std::sync::mpsc::Receiver;
 
+#[cfg(feature = "esp")]
 use esp_idf_hal::delay::FreeRtos;
-use esp_idf_hal::rmt::{FixedLengthSignal, PinState, Pulse, PulseTicks, TxRmtDriver};
-
-#[derive(Debug)]
-pub struct Note(pub u16);
-
-pub const B0: Note = Note(31);
-pub const C1: Note = Note(33);
-pub const CS1: Note = Note(35);
-pub const D1: Note = Note(37);
-pub const DS1: Note = Note(39);
-pub const E1: Note = Note(41);
-pub const F1: Note = Note(44);
-pub const FS1: Note = Note(46);
-pub const G1: Note = Note(49);
-pub const GS1: Note = Note(52);
-pub const A1: Note = Note(55);
-pub const AS1: Note = Note(58);
-pub const B1: Note = Note(62);
-pub const C2: Note = Note(65);
-pub const CS2: Note = Note(69);
-pub const D2: Note = Note(73);
-pub const DS2: Note = Note(78);
-pub const E2: Note = Note(82);
-pub const F2: Note = Note(87);
-pub const FS2: Note = Note(93);
-pub const G2: Note = Note(98);
-pub const GS2: Note = Note(104);
-pub const A2: Note = Note(110);
-pub const AS2: Note = Note(117);
-pub const B2: Note = Note(123);
-pub const C3: Note = Note(131);
-pub const CS3: Note = Note(139);
-pub const D3: Note = Note(147);
-pub const DS3: Note = Note(156);
-pub const E3: Note = Note(165);
-pub const F3: Note = Note(175);
-pub const FS3: Note = Note(185);
-pub const G3: Note = Note(196);
-pub const GS3: Note = Note(208);
-pub const A3: Note = Note(220);
-pub const AS3: Note = Note(233);
-pub const B3: Note = Note(247);
-pub const C4: Note = Note(262);
-pub const CS4: Note = Note(277);
-pub const D4: Note = Note(294);
-pub const DS4: Note = Note(311);
-pub const E4: Note = Note(330);
-pub const F4: Note = Note(349);
-pub const FS4: Note = Note(370);
-pub const G4: Note = Note(392);
-pub const GS4: Note = Note(415);
-pub const A4: Note = Note(440);
-pub const AS4: Note = Note(466);
-pub const B4: Note = Note(494);
-pub const C5: Note = Note(523);
-pub const CS5: Note = Note(554);
-pub const D5: Note = Note(587);
-pub const DS5: Note = Note(622);
-pub const E5: Note = Note(659);
-pub const F5: Note = Note(698);
-pub const FS5: Note = Note(740);
-pub const G5: Note = Note(784);
-pub const GS5: Note = Note(831);
-pub const A5: Note = Note(880);
-pub const AS5: Note = Note(932);
-pub const B5: Note = Note(988);
-pub const C6: Note = Note(1047);
-pub const CS6: Note = Note(1109);
-pub const D6: Note = Note(1175);
-pub const DS6: Note = Note(1245);
-pub const E6: Note = Note(1319);
-pub const F6: Note = Note(1397);
-pub const FS6: Note = Note(1480);
-pub const G6: Note = Note(1568);
-pub const GS6: Note = Note(1661);
-pub const A6: Note = Note(1760);
-pub const AS6: Note = Note(1865);
-pub const B6: Note = Note(1976);
-pub const C7: Note = Note(2093);
-pub const CS7: Note = Note(2217);
-pub const D7: Note = Note(2349);
-pub const DS7: Note = Note(2489);
-pub const E7: Note = Note(2637);
-pub const F7: Note = Note(2794);
-pub const FS7: Note = Note(2960);
-pub const G7: Note = Note(3136);
-pub const GS7: Note = Note(3322);
-pub const A7: Note = Note(3520);
-pub const AS7: Note = Note(3729);
-pub const B7: Note = Note(3951);
-pub const C8: Note = Note(4186);
-pub const CS8: Note = Note(4435);
-pub const D8: Note = Note(4699);
-pub const DS8: Note = Note(4978);
-pub const REST: Note = Note(0);
+#[cfg(feature = "esp")]
+use esp_idf_hal::rmt::TxRmtDriver;
+#[cfg(feature = "esp")]
+use shared::sound::play_pulses;
+use shared::sound_effect::MAX_PULSE_TICKS;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Note {
+    Rest,
+    // MIDI note number, 69 is A4
+    Pitch(u8),
+}
+
+pub const B0: Note = Note::Pitch(23);
+pub const C1: Note = Note::Pitch(24);
+pub const CS1: Note = Note::Pitch(25);
+pub const D1: Note = Note::Pitch(26);
+pub const DS1: Note = Note::Pitch(27);
+pub const E1: Note = Note::Pitch(28);
+pub const F1: Note = Note::Pitch(29);
+pub const FS1: Note = Note::Pitch(30);
+pub const G1: Note = Note::Pitch(31);
+pub const GS1: Note = Note::Pitch(32);
+pub const A1: Note = Note::Pitch(33);
+pub const AS1: Note = Note::Pitch(34);
+pub const B1: Note = Note::Pitch(35);
+pub const C2: Note = Note::Pitch(36);
+pub const CS2: Note = Note::Pitch(37);
+pub const D2: Note = Note::Pitch(38);
+pub const DS2: Note = Note::Pitch(39);
+pub const E2: Note = Note::Pitch(40);
+pub const F2: Note = Note::Pitch(41);
+pub const FS2: Note = Note::Pitch(42);
+pub const G2: Note = Note::Pitch(43);
+pub const GS2: Note = Note::Pitch(44);
+pub const A2: Note = Note::Pitch(45);
+pub const AS2: Note = Note::Pitch(46);
+pub const B2: Note = Note::Pitch(47);
+pub const C3: Note = Note::Pitch(48);
+pub const CS3: Note = Note::Pitch(49);
+pub const D3: Note = Note::Pitch(50);
+pub const DS3: Note = Note::Pitch(51);
+pub const E3: Note = Note::Pitch(52);
+pub const F3: Note = Note::Pitch(53);
+pub const FS3: Note = Note::Pitch(54);
+pub const G3: Note = Note::Pitch(55);
+pub const GS3: Note = Note::Pitch(56);
+pub const A3: Note = Note::Pitch(57);
+pub const AS3: Note = Note::Pitch(58);
+pub const B3: Note = Note::Pitch(59);
+pub const C4: Note = Note::Pitch(60);
+pub const CS4: Note = Note::Pitch(61);
+pub const D4: Note = Note::Pitch(62);
+pub const DS4: Note = Note::Pitch(63);
+pub const E4: Note = Note::Pitch(64);
+pub const F4: Note = Note::Pitch(65);
+pub const FS4: Note = Note::Pitch(66);
+pub const G4: Note = Note::Pitch(67);
+pub const GS4: Note = Note::Pitch(68);
+pub const A4: Note = Note::Pitch(69);
+pub const AS4: Note = Note::Pitch(70);
+pub const B4: Note = Note::Pitch(71);
+pub const C5: Note = Note::Pitch(72);
+pub const CS5: Note = Note::Pitch(73);
+pub const D5: Note = Note::Pitch(74);
+pub const DS5: Note = Note::Pitch(75);
+pub const E5: Note = Note::Pitch(76);
+pub const F5: Note = Note::Pitch(77);
+pub const FS5: Note = Note::Pitch(78);
+pub const G5: Note = Note::Pitch(79);
+pub const GS5: Note = Note::Pitch(80);
+pub const A5: Note = Note::Pitch(81);
+pub const AS5: Note = Note::Pitch(82);
+pub const B5: Note = Note::Pitch(83);
+pub const C6: Note = Note::Pitch(84);
+pub const CS6: Note = Note::Pitch(85);
+pub const D6: Note = Note::Pitch(86);
+pub const DS6: Note = Note::Pitch(87);
+pub const E6: Note = Note::Pitch(88);
+pub const F6: Note = Note::Pitch(89);
+pub const FS6: Note = Note::Pitch(90);
+pub const G6: Note = Note::Pitch(91);
+pub const GS6: Note = Note::Pitch(92);
+pub const A6: Note = Note::Pitch(93);
+pub const AS6: Note = Note::Pitch(94);
+pub const B6: Note = Note::Pitch(95);
+pub const C7: Note = Note::Pitch(96);
+pub const CS7: Note = Note::Pitch(97);
+pub const D7: Note = Note::Pitch(98);
+pub const DS7: Note = Note::Pitch(99);
+pub const E7: Note = Note::Pitch(100);
+pub const F7: Note = Note::Pitch(101);
+pub const FS7: Note = Note::Pitch(102);
+pub const G7: Note = Note::Pitch(103);
+pub const GS7: Note = Note::Pitch(104);
+pub const A7: Note = Note::Pitch(105);
+pub const AS7: Note = Note::Pitch(106);
+pub const B7: Note = Note::Pitch(107);
+pub const C8: Note = Note::Pitch(108);
+pub const CS8: Note = Note::Pitch(109);
+pub const D8: Note = Note::Pitch(110);
+pub const DS8: Note = Note::Pitch(111);
+pub const REST: Note = Note::Rest;
+
+// The buzzer can't go lower than B0 because of the RMT tick limit, DS8 is as high as the songs go
+pub const LOWEST_NOTE: Note = B0;
+pub const HIGHEST_NOTE: Note = DS8;
+
+// The RMT transmitter counts the 80 MHz APB clock divided by this, the player and the renderer both rely on it
+pub const RMT_CLOCK_DIVIDER: u8 = 80;
+pub const RMT_COUNTER_CLOCK_HZ: u32 = 80_000_000 / RMT_CLOCK_DIVIDER as u32;
+
+pub const MAX_VOLUME: u8 = 100;
+// Notes take 90% of their written length, the pace the songs were always played at
+const NOTE_LENGTH: f32 = 0.9;
+pub const MIN_TEMPO: f32 = 0.25;
+pub const MAX_TEMPO: f32 = 4.0;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Tuning {
+    // Frequency of A4 in Hz
+    pub reference: f32,
+    // Shifts every note by a fraction of a semitone, 100 cents make a semitone
+    pub cents: f32,
+}
+
+// Range of A4 the tuning accepts, from baroque pitch to the highest orchestras go
+pub const MIN_REFERENCE: f32 = 400.0;
+pub const MAX_REFERENCE: f32 = 480.0;
+pub const MAX_CENTS: f32 = 100.0;
+
+impl Default for Tuning {
+    fn default() -> Self {
+        Self { reference: 440.0, cents: 0.0 }
+    }
+}
+
+impl Tuning {
+    /// Keeps the reference pitch and cents within range, values that are not a number fall back to the defaults.
+    pub fn clamped(self) -> Self {
+        let default = Tuning::default();
+
+        let reference = match self.reference.is_finite() {
+            true => self.reference.clamp(MIN_REFERENCE, MAX_REFERENCE),
+            false => default.reference,
+        };
+
+        let cents = match self.cents.is_finite() {
+            true => self.cents.clamp(-MAX_CENTS, MAX_CENTS),
+            false => default.cents,
+        };
+
+        Self { reference, cents }
+    }
+}
+
+impl Note {
+    pub const fn midi(self) -> Option<u8> {
+        match self {
+            Note::Rest => None,
+            Note::Pitch(number) => Some(number),
+        }
+    }
+
+    /// Frequency in Hz for the given tuning, 0 for rests.
+    pub fn frequency(self, tuning: &Tuning) -> f32 {
+        match self {
+            Note::Rest => 0.0,
+            Note::Pitch(number) => {
+                let semitones = number as f32 - 69.0 + tuning.cents / 100.0;
+                tuning.reference * 2f32.powf(semitones / 12.0)
+            }
+        }
+    }
+
+    /// Moves the note by the given amount of semitones, clamped to the lowest and highest notes available.
+    pub fn transpose(self, semitones: i8) -> Note {
+        match (self, LOWEST_NOTE, HIGHEST_NOTE) {
+            (Note::Pitch(number), Note::Pitch(lowest), Note::Pitch(highest)) => {
+                Note::Pitch((number as i32 + semitones as i32).clamp(lowest as i32, highest as i32) as u8)
+            }
+            _ => self,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Articulation {
+    // Notes take their whole written length and run into each other, so the song plays a little slower
+    Legato,
+    // Notes are played for 90% of their written length, as the songs always were
+    Normal,
+    // Notes are short with a long silence after them, keeping the pace of Normal
+    Staccato,
+}
+
+impl Articulation {
+    // How much of the written length the note lasts
+    fn length(&self) -> f32 {
+        match self {
+            Articulation::Legato => 1.0,
+            Articulation::Normal | Articulation::Staccato => NOTE_LENGTH,
+        }
+    }
+
+    // How much of the note is actually sounded, the rest is a silent gap within the same note
+    fn ratio(&self) -> f32 {
+        match self {
+            Articulation::Legato | Articulation::Normal => 1.0,
+            Articulation::Staccato => 0.5,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Parameters {
+    pub volume: u8,
+    // Multiplies the tempo of the song, 1.0 plays it as written
+    pub tempo: f32,
+    // Semitones to shift every note by
+    pub transpose: i8,
+    pub articulation: Articulation,
+    pub tuning: Tuning,
+}
+
+impl Default for Parameters {
+    fn default() -> Self {
+        Self {
+            volume: MAX_VOLUME,
+            tempo: 1.0,
+            transpose: 0,
+            articulation: Articulation::Normal,
+            tuning: Tuning::default(),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Playback {
+    // Every note of the song has been played
+    Finished,
+    // The song was interrupted by the stop signal
+    Stopped,
+    // A note could not be played, the song was cut short
+    Failed,
+}
 
 pub trait Song {
     fn tempo(&self) -> f32;
 
     fn notes(&self) -> &[(Note, i8)];
 
-    fn play(&mut self, tx: &mut TxRmtDriver<'static>, stop_signal: Receiver<()>) -> anyhow::Result<()> {
-        let whole_note: f32 = (60000.0 * 4.0) / self.tempo();
+    /// Whether each note is tied to the one after it, notes past the end of the slice are not tied.
+    fn ties(&self) -> &[bool] {
+        &[]
+    }
 
-        for (note, divider) in self.notes() {
+    /// Returns how long the note lasts and how much of it is sounded, both in milliseconds.
+    /// The silent gap of staccato notes is part of the note, so they keep the pace of normal ones.
+    /// A tied note is sounded for its whole duration so it flows into the next one.
+    fn timing(&self, divider: i8, tied: bool, parameters: &Parameters) -> (f32, f32) {
+        let whole_note: f32 = (60000.0 * 4.0) / (self.tempo() * parameters.tempo);
+
+        // Negative dividers are dotted notes, which last one and a half times longer
+        let written = match divider > 0 {
+            true => whole_note / divider as f32,
+            false => whole_note / divider.abs() as f32 * 1.5,
+        };
+
+        let note_duration = written * parameters.articulation.length();
+
+        match tied {
+            true => (note_duration, note_duration),
+            false => (note_duration, note_duration * parameters.articulation.ratio()),
+        }
+    }
+
+    #[cfg(feature = "esp")]
+    fn play(&mut self, tx: &mut TxRmtDriver<'static>, stop_signal: Receiver<()>, parameters: &Mutex<Parameters>) -> anyhow::Result<Playback> {
+        for (index, (note, divider)) in self.notes().iter().enumerate() {
             if let Ok(_) = stop_signal.try_recv() {
-                return Ok(());
+                return Ok(Playback::Stopped);
             }
 
-            let mut note_duration: f32 = 0.0;
+            // Read the parameters on every note so changes are heard while the song is playing
+            let parameters = match parameters.lock() {
+                Ok(parameters) => *parameters,
+                Err(_) => Parameters::default(),
+            };
 
-            if divider > &0 {
-                note_duration = whole_note / *divider as f32;
-            } else {
-                note_duration = whole_note / divider.abs() as f32;
-                note_duration *= 1.5;
-            }
+            let tied = self.ties().get(index).copied().unwrap_or(false);
+            let (note_duration, sounded) = self.timing(*divider, tied, &parameters);
+            let frequency = note.transpose(parameters.transpose).frequency(&parameters.tuning);
+
+            self.play_pitch(tx, frequency, sounded, parameters.volume)?;
 
-            self.play_pitch(tx, note.0, note_duration * 0.9)?;
+            if sounded < note_duration {
+                FreeRtos::delay_ms((note_duration - sounded) as u32);
+            }
         }
 
-        Ok(())
+        Ok(Playback::Finished)
     }
 
-    fn play_pitch(&self, transmitter: &mut TxRmtDriver<'static>, pitch: u16, duration: f32) -> anyhow::Result<()> {
-        if pitch == 0 {
+    #[cfg(feature = "esp")]
+    fn play_pitch(&self, transmitter: &mut TxRmtDriver<'static>, frequency: f32, duration: f32, volume: u8) -> anyhow::Result<()> {
+        if frequency <= 0.0 || volume == 0 {
             FreeRtos::delay_ms(duration as u32);
             return Ok(());
         }
 
-        let ticks_hz = transmitter.counter_clock()?;
-        let tick_count = (ticks_hz.0 / pitch as u32 / 2) as u16;
-        let ticks = PulseTicks::new(tick_count)?;
-
-        let on = Pulse::new(PinState::High, ticks);
-        let off = Pulse::new(PinState::Low, ticks);
-        let mut signal = FixedLengthSignal::<1>::new();
+        let (high_ticks, low_ticks) = pulse_widths(period_ticks(frequency), volume);
 
-        signal.set(0, &(on, off))?;
-
-        transmitter.start(signal)?;
+        play_pulses(transmitter, high_ticks as u32, low_ticks as u32, duration as u32)
+    }
+}
 
-        FreeRtos::delay_ms(duration as u32);
+/// Length of one period of the frequency in RMT ticks.
+pub fn period_ticks(frequency: f32) -> u32 {
+    (RMT_COUNTER_CLOCK_HZ as f32 / frequency) as u32
+}
 
-        transmitter.stop()?;
+// The buzzer is loudest with a 50% duty cycle, so the volume shrinks the high
+// pulse from half of the period (100) down to a single tick (1) while the low
+// pulse takes the remainder, keeping the period and therefore the pitch intact.
+// Pulses longer than the RMT peripheral can hold are cut to MAX_PULSE_TICKS.
+pub fn pulse_widths(period: u32, volume: u8) -> (u16, u16) {
+    let half = period / 2;
+    let high = (half * volume.min(MAX_VOLUME) as u32 / MAX_VOLUME as u32).clamp(1, half.max(1));
+    let low = period.saturating_sub(high).max(1);
 
-        Ok(())
-    }
+    (high.min(MAX_PULSE_TICKS) as u16, low.min(MAX_PULSE_TICKS) as u16)
 }
@@ -2,3 +2,23 @@ pub mod tetris;
 pub mod super_mario_bros;
 pub mod the_lion_sleeps_tonight;
 pub mod green_hill;
+#[cfg(test)]
+pub mod original;
+
+use crate::song::Song;
+use crate::songs::green_hill::GreenHill;
+use crate::songs::super_mario_bros::SuperMarioBros;
+use crate::songs::tetris::Tetris;
+use crate::songs::the_lion_sleeps_tonight::TheLionSleepsTonight;
+
+pub type SongFactory = fn() -> Box<dyn Song>;
+
+/// Every bundled song in playback order, along with a name to look it up by.
+pub fn all() -> Vec<(&'static str, SongFactory)> {
+    vec![
+        ("green-hill", || Box::new(GreenHill::new())),
+        ("the-lion-sleeps-tonight", || Box::new(TheLionSleepsTonight::new())),
+        ("super-mario-bros", || Box::new(SuperMarioBros::new())),
+        ("tetris", || Box::new(Tetris::new())),
+    ]
+}
@@ -0,0 +1,259 @@
+//! The note tables of the bundled songs as they were written before the notation existed,
+//! the tests check that the notation still expands to exactly these notes.
+
+use crate::song::*;
+
+pub const GREEN_HILL: &[(Note, i8)] = &[
+    (REST, 2), (D5, 8), (B4, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    (REST, 8), (A4, 8), (FS5, 8), (E5, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    (REST, 4), (D5, 8), (B4, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    //--------------------------------------------------------------------------------------
+    (REST, 8), (B4, 8), (B4, 8), (G4, 4), (B4, 8),
+    (A4, 4), (B4, 8), (A4, 4), (D4, 2),
+    (REST, 4), (D5, 8), (B4, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    (REST, 8), (A4, 8), (FS5, 8), (E5, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    //--------------------------------------------------------------------------------------
+    (REST, 4), (D5, 8), (B4, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    (REST, 8), (B4, 8), (B4, 8), (G4, 4), (B4, 8),
+    (A4, 4), (B4, 8), (A4, 4), (D4, 8), (D4, 8), (FS4, 8),
+    (E4, -1),
+    (REST, 8), (D4, 8), (E4, 8), (FS4, -1),
+    //--------------------------------------------------------------------------------------
+    (REST, 8), (D4, 8), (D4, 8), (FS4, 8), (F4, -1),
+    (REST, 8), (D4, 8), (F4, 8), (E4, -1),
+    //--------------------------------------------------------------------------------------
+    (REST, 2), (D5, 8), (B4, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    (REST, 8), (A4, 8), (FS5, 8), (E5, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    (REST, 4), (D5, 8), (B4, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    //--------------------------------------------------------------------------------------
+    (REST, 8), (B4, 8), (B4, 8), (G4, 4), (B4, 8),
+    (A4, 4), (B4, 8), (A4, 4), (D4, 2),
+    (REST, 4), (D5, 8), (B4, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    (REST, 8), (A4, 8), (FS5, 8), (E5, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    //--------------------------------------------------------------------------------------
+    (REST, 4), (D5, 8), (B4, 4), (D5, 8),
+    (CS5, 4), (D5, 8), (CS5, 4), (A4, 2),
+    (REST, 8), (B4, 8), (B4, 8), (G4, 4), (B4, 8),
+    (A4, 4), (B4, 8), (A4, 4), (D4, 8), (D4, 8), (FS4, 8),
+    (E4, -1),
+    (REST, 8), (D4, 8), (E4, 8), (FS4, -1),
+    //--------------------------------------------------------------------------------------
+    (REST, 8), (D4, 8), (D4, 8), (FS4, 8), (F4, -1),
+    (REST, 8), (D4, 8), (F4, 8), (E4, 8),
+    (E4, -2), (A4, 8), (CS5, 8),
+    (FS5, 8), (E5, 4), (D5, 8), (A5, -4),
+];
+
+pub const THE_LION_SLEEPS_TONIGHT: &[(Note, i8)] = &[
+    (F4, 4), (G4, 4), (A4, 8), (G4, 4), (A4, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (C4, 8), (C4, 4), (C4, 8), (C4, 4),
+    (C4, 1),
+    //--------------------------------------------------------------------------------------
+    (F4, 4), (G4, 4), (A4, 8), (G4, 4), (A4, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (C4, 8), (C4, 4), (C4, 8), (C4, 4),
+    (C4, -2), (REST, -8), (A4, 16),
+    //--------------------------------------------------------------------------------------
+    (A4, -8), (A4, 16), (A4, -8), (A4, 16), (A4, -8), (A4, 16), (A4, -8), (A4, 16),
+    (AS4, -8), (AS4, 16), (AS4, -8), (AS4, 16), (AS4, -8), (AS4, 16), (AS4, -8), (AS4, 16),
+    (A4, -8), (A4, 16), (A4, -8), (A4, 16), (A4, -8), (A4, 16), (A4, -8), (A4, 16),
+    (G4, -8), (G4, 16), (G4, -8), (G4, 16), (G4, -8), (G4, 16), (G4, -8), (G4, 16),
+    //--------------------------------------------------------------------------------------
+    (A4, -8), (A4, 16), (A4, -8), (A4, 16), (A4, -8), (A4, 16), (A4, -8), (A4, 16),
+    (AS4, -8), (AS4, 16), (AS4, -8), (AS4, 16), (AS4, -8), (AS4, 16), (AS4, -8), (AS4, 16),
+    (A4, -8), (A4, 16), (A4, -8), (A4, 16), (A4, -8), (A4, 16), (A4, -8), (A4, 16),
+    (G4, -8), (G4, 16), (G4, -8), (G4, 16), (G4, -8), (G4, 16), (G4, -8), (G4, 16),
+    //--------------------------------------------------------------------------------------
+    (F4, 4), (G4, 4), (A4, 8), (G4, 4), (A4, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (G4, 4), (F4, 4), (A4, 4),
+    (G4, 1),
+    (C5, 4), (A4, 4), (G4, 8), (A4, 4), (C5, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (G4, 4), (F4, 4), (A4, 4),
+    (G4, 1),
+    //--------------------------------------------------------------------------------------
+    (C5, 1),
+    (C5, 4), (AS4, 8), (C5, 8), (AS4, 2),
+    (A4, 4), (C4, 8), (C4, 4), (C4, 8), (C4, 4),
+    (C4, 1),
+    //--------------------------------------------------------------------------------------
+    (REST, 4), (A4, 8), (G4, 8), (F4, 8), (E4, 8), (D4, 8), (C4, 8),
+    (D4, 1),
+    (REST, 4), (A4, 8), (G4, 8), (F4, 8), (E4, 8), (D4, 8), (C4, 8),
+    (D4, 1),
+    //--------------------------------------------------------------------------------------
+    (F4, 4), (G4, 4), (A4, 8), (G4, 4), (A4, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (G4, 4), (F4, 4), (A4, 4),
+    (G4, 1),
+    (C5, 4), (A4, 4), (G4, 8), (A4, 4), (C5, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (G4, 4), (F4, 4), (A4, 4),
+    (G4, 1),
+    //--------------------------------------------------------------------------------------
+    (C5, 1),
+    (C5, 4), (AS4, 8), (C5, 8), (AS4, 2),
+    (A4, 4), (C4, 8), (C4, 4), (C4, 8), (C4, 4),
+    (C4, 1),
+    //--------------------------------------------------------------------------------------
+    (REST, 4), (A4, 8), (G4, 8), (F4, 8), (E4, 8), (D4, 8), (C4, 8),
+    (D4, 1),
+    (REST, 4), (A4, 8), (G4, 8), (F4, 8), (E4, 8), (D4, 8), (C4, 8),
+    (D4, 1),
+    //--------------------------------------------------------------------------------------
+    (F4, 4), (G4, 4), (A4, 8), (G4, 4), (A4, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (C4, 8), (C4, 4), (C4, 8), (C4, 4),
+    (C4, 1),
+    //--------------------------------------------------------------------------------------
+    (F4, 4), (G4, 4), (A4, 8), (G4, 4), (A4, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (G4, 4), (F4, 4), (A4, 4),
+    (G4, 1),
+    (C5, 4), (A4, 4), (G4, 8), (A4, 4), (C5, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (G4, 4), (F4, 4), (A4, 4),
+    (G4, 1),
+    //--------------------------------------------------------------------------------------
+    (C5, 1),
+    (C5, 4), (AS4, 8), (C5, 8), (AS4, 2),
+    (A4, 4), (C4, 8), (C4, 4), (C4, 8), (C4, 4),
+    (C4, 1),
+    //--------------------------------------------------------------------------------------
+    (REST, 4), (A4, 8), (G4, 8), (F4, 8), (E4, 8), (D4, 8), (C4, 8),
+    (D4, 1),
+    (REST, 4), (A4, 8), (G4, 8), (F4, 8), (E4, 8), (D4, 8), (C4, 8),
+    (D4, 1),
+    //--------------------------------------------------------------------------------------
+    (F4, 4), (G4, 4), (A4, 8), (G4, 4), (A4, 8),
+    (AS4, 4), (A4, 4), (G4, 8), (F4, 4), (G4, 8),
+    (A4, 4), (C4, 8), (C4, 4), (C4, 8), (C4, 4),
+    (C4, 1),
+];
+
+pub const SUPER_MARIO_BROS: &[(Note, i8)] = &[
+    (E5, 8), (E5, 8), (REST, 8), (E5, 8), (REST, 8), (C5, 8), (E5, 8),
+    (G5, 4), (REST, 4), (G4, 8), (REST, 4),
+    (C5, -4), (G4, 8), (REST, 4), (E4, -4),
+    (A4, 4), (B4, 4), (AS4, 8), (A4, 4),
+    (G4, -8), (E5, -8), (G5, -8), (A5, 4), (F5, 8), (G5, 8),
+    (REST, 8), (E5, 4), (C5, 8), (D5, 8), (B4, -4),
+    (C5, -4), (G4, 8), (REST, 4), (E4, -4),
+    (A4, 4), (B4, 4), (AS4, 8), (A4, 4),
+    (G4, -8), (E5, -8), (G5, -8), (A5, 4), (F5, 8), (G5, 8),
+    (REST, 8), (E5, 4), (C5, 8), (D5, 8), (B4, -4),
+    //--------------------------------------------------------------------------------------
+    (REST, 4), (G5, 8), (FS5, 8), (F5, 8), (DS5, 4), (E5, 8),//7
+    (REST, 8), (GS4, 8), (A4, 8), (C4, 8), (REST, 8), (A4, 8), (C5, 8), (D5, 8),
+    (REST, 4), (DS5, 4), (REST, 8), (D5, -4),
+    (C5, 2), (REST, 2),
+    //--------------------------------------------------------------------------------------
+    (REST, 4), (G5, 8), (FS5, 8), (F5, 8), (DS5, 4), (E5, 8),//repeats from 7
+    (REST, 8), (GS4, 8), (A4, 8), (C4, 8), (REST, 8), (A4, 8), (C5, 8), (D5, 8),
+    (REST, 4), (DS5, 4), (REST, 8), (D5, -4),
+    (C5, 2), (REST, 2),
+    //--------------------------------------------------------------------------------------
+    (C5, 8), (C5, 4), (C5, 8), (REST, 8), (C5, 8), (D5, 4),//11
+    (E5, 8), (C5, 4), (A4, 8), (G4, 2),
+    //--------------------------------------------------------------------------------------
+    (C5, 8), (C5, 4), (C5, 8), (REST, 8), (C5, 8), (D5, 8), (E5, 8),//13
+    (REST, 1),
+    (C5, 8), (C5, 4), (C5, 8), (REST, 8), (C5, 8), (D5, 4),
+    (E5, 8), (C5, 4), (A4, 8), (G4, 2),
+    (E5, 8), (E5, 8), (REST, 8), (E5, 8), (REST, 8), (C5, 8), (E5, 4),
+    (G5, 4), (REST, 4), (G4, 4), (REST, 4),
+    (C5, -4), (G4, 8), (REST, 4), (E4, -4), // 19
+    //--------------------------------------------------------------------------------------
+    (A4, 4), (B4, 4), (AS4, 8), (A4, 4),
+    (G4, -8), (E5, -8), (G5, -8), (A5, 4), (F5, 8), (G5, 8),
+    (REST, 8), (E5, 4), (C5, 8), (D5, 8), (B4, -4),
+    //--------------------------------------------------------------------------------------
+    (C5, -4), (G4, 8), (REST, 4), (E4, -4), // repeats from 19
+    (A4, 4), (B4, 4), (AS4, 8), (A4, 4),
+    (G4, -8), (E5, -8), (G5, -8), (A5, 4), (F5, 8), (G5, 8),
+    (REST, 8), (E5, 4), (C5, 8), (D5, 8), (B4, -4),
+    //--------------------------------------------------------------------------------------
+    (E5, 8), (C5, 4), (G4, 8), (REST, 4), (GS4, 4),//23
+    (A4, 8), (F5, 4), (F5, 8), (A4, 2),
+    (D5, -8), (A5, -8), (A5, -8), (A5, -8), (G5, -8), (F5, -8),
+    //--------------------------------------------------------------------------------------
+    (E5, 8), (C5, 4), (A4, 8), (G4, 2), //26
+    (E5, 8), (C5, 4), (G4, 8), (REST, 4), (GS4, 4),
+    (A4, 8), (F5, 4), (F5, 8), (A4, 2),
+    (B4, 8), (F5, 4), (F5, 8), (F5, -8), (E5, -8), (D5, -8),
+    (C5, 8), (E4, 4), (E4, 8), (C4, 2),
+    //--------------------------------------------------------------------------------------
+    (E5, 8), (C5, 4), (G4, 8), (REST, 4), (GS4, 4),//repeats from 23
+    (A4, 8), (F5, 4), (F5, 8), (A4, 2),
+    (D5, -8), (A5, -8), (A5, -8), (A5, -8), (G5, -8), (F5, -8),
+    //--------------------------------------------------------------------------------------
+    (E5, 8), (C5, 4), (A4, 8), (G4, 2), //26
+    (E5, 8), (C5, 4), (G4, 8), (REST, 4), (GS4, 4),
+    (A4, 8), (F5, 4), (F5, 8), (A4, 2),
+    (B4, 8), (F5, 4), (F5, 8), (F5, -8), (E5, -8), (D5, -8),
+    (C5, 8), (E4, 4), (E4, 8), (C4, 2),
+    (C5, 8), (C5, 4), (C5, 8), (REST, 8), (C5, 8), (D5, 8), (E5, 8),
+    (REST, 1),
+    //--------------------------------------------------------------------------------------
+    (C5, 8), (C5, 4), (C5, 8), (REST, 8), (C5, 8), (D5, 4), //33
+    (E5, 8), (C5, 4), (A4, 8), (G4, 2),
+    (E5, 8), (E5, 8), (REST, 8), (E5, 8), (REST, 8), (C5, 8), (E5, 4),
+    (G5, 4), (REST, 4), (G4, 4), (REST, 4),
+    (E5, 8), (C5, 4), (G4, 8), (REST, 4), (GS4, 4),
+    (A4, 8), (F5, 4), (F5, 8), (A4, 2),
+    (D5, -8), (A5, -8), (A5, -8), (A5, -8), (G5, -8), (F5, -8),
+    //--------------------------------------------------------------------------------------
+    (E5, 8), (C5, 4), (A4, 8), (G4, 2), //40
+    (E5, 8), (C5, 4), (G4, 8), (REST, 4), (GS4, 4),
+    (A4, 8), (F5, 4), (F5, 8), (A4, 2),
+    (B4, 8), (F5, 4), (F5, 8), (F5, -8), (E5, -8), (D5, -8),
+    (C5, 8), (E4, 4), (E4, 8), (C4, 2),
+    //--------------------------------------------------------------------------------------
+    (C5, -4), (G4, -4), (E4, 4), //45
+    (A4, -8), (B4, -8), (A4, -8), (GS4, -8), (AS4, -8), (GS4, -8),
+    (G4, 8), (D4, 8), (E4, -2),
+];
+
+pub const TETRIS: &[(Note, i8)] = &[
+    (E5, 4), (B4, 8), (C5, 8), (D5, 4), (C5, 8), (B4, 8),
+    (A4, 4), (A4, 8), (C5, 8), (E5, 4), (D5, 8), (C5, 8),
+    (B4, -4), (C5, 8), (D5, 4), (E5, 4),
+    (C5, 4), (A4, 4), (A4, 8), (A4, 4), (B4, 8), (C5, 8),
+    // -------------------------------------------------------------------------------------
+    (D5, -4), (F5, 8), (A5, 4), (G5, 8), (F5, 8),
+    (E5, -4), (C5, 8), (E5, 4), (D5, 8), (C5, 8),
+    (B4, 4), (B4, 8), (C5, 8), (D5, 4), (E5, 4),
+    (C5, 4), (A4, 4), (A4, 4), (REST, 4),
+    // -------------------------------------------------------------------------------------
+    (E5, 4), (B4, 8), (C5, 8), (D5, 4), (C5, 8), (B4, 8),
+    (A4, 4), (A4, 8), (C5, 8), (E5, 4), (D5, 8), (C5, 8),
+    (B4, -4), (C5, 8), (D5, 4), (E5, 4),
+    (C5, 4), (A4, 4), (A4, 8), (A4, 4), (B4, 8), (C5, 8),
+    // -------------------------------------------------------------------------------------
+    (D5, -4), (F5, 8), (A5, 4), (G5, 8), (F5, 8),
+    (E5, -4), (C5, 8), (E5, 4), (D5, 8), (C5, 8),
+    (B4, 4), (B4, 8), (C5, 8), (D5, 4), (E5, 4),
+    (C5, 4), (A4, 4), (A4, 4), (REST, 4),
+    // -------------------------------------------------------------------------------------
+    (E5, 2), (C5, 2),
+    (D5, 2), (B4, 2),
+    (C5, 2), (A4, 2),
+    (GS4, 2), (B4, 4), (REST, 8),
+    (E5, 2), (C5, 2),
+    (D5, 2), (B4, 2),
+    (C5, 4), (E5, 4), (A5, 2),
+    (GS5, 2),
+];
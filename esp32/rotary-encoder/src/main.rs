@@ -1,31 +1,43 @@
 use std::fmt::{Display, Formatter};
-use std::time::Duration;
+use std::time::{Duration, Instant};
 
 use button_driver::{Button, ButtonConfig};
 use esp_idf_hal::delay::FreeRtos;
-use esp_idf_hal::gpio::{Gpio1, Gpio43, Input, PinDriver};
+use esp_idf_hal::gpio::{Gpio18, Gpio44, PinDriver};
 use esp_idf_hal::peripherals::Peripherals;
 use rotary_encoder_embedded::{Direction, RotaryEncoder};
-use rotary_encoder_embedded::standard::StandardMode;
+use shared::nvs_storage::NvsStorage;
+use shared::segment_display::{MAX_BRIGHTNESS, SegmentDisplay};
+use shared::settings::{Key, Settings};
+use shared::seven_segment::Align;
+use shared::sound::{Effect, Sound};
 
-// Hex digits from 0 to F
-const DIGITS: [u8; 16] = [
-    0x3f, 0x06, 0x5b, 0x4f,
-    0x66, 0x6d, 0x7d, 0x07,
-    0x7f, 0x6f, 0x77, 0x7c,
-    0x39, 0x5e, 0x79, 0x71,
-];
+use rotary_encoder::timer::{Countdown, Interval, seconds_left, Stopwatch};
 
-const MAX_BRIGHTNESS: u8 = 7;
 const MAX_COUNTER: u16 = 9999;
+// Longest time the mm:ss display can show
+const MAX_TIMER_SECONDS: u32 = 99 * 60 + 59;
+// The longest duration shown as hh:mm, a stopwatch left running past it stays there
+const MAX_DISPLAYED_SECONDS: u32 = MAX_TIMER_SECONDS * 60;
+// How much each step of the encoder changes the countdown and interval durations
+const TIMER_STEP_SECONDS: u32 = 10;
+// How long a lap time stays on the display
+const LAP_DISPLAY: Duration = Duration::from_secs(3);
+// Set to false when no buzzer is connected
+const BUZZER_ENABLED: bool = true;
+
+// Bump when the stored settings change shape
+const SETTINGS_VERSION: u32 = 1;
+const COUNTER: Key<u16> = Key::new("counter", 0);
+const BRIGHTNESS: Key<u8> = Key::new("brightness", 5);
+const COUNTDOWN_SECONDS: Key<u32> = Key::new("countdown", 5 * 60);
+const INTERVAL_SECONDS: Key<u32> = Key::new("interval", 60);
+
+type SevenSegment = SegmentDisplay<'static, Gpio18, Gpio44>;
 
 #[derive(Debug)]
 enum CustomError {
     UnableToTakePeripherals,
-    UnableToSetBrightness,
-    UnableToPrint,
-    FailedToInitializeDisplay,
-    FailedToClearDisplay,
 }
 
 impl std::error::Error for CustomError {}
@@ -36,19 +48,57 @@ impl Display for CustomError {
     }
 }
 
-#[derive(PartialEq, Debug)]
+#[derive(PartialEq, Debug, Copy, Clone)]
 enum Mode {
     Counter,
     SetBrightness,
+    // Picks which of the modes below is used
+    Menu,
+    Stopwatch,
+    Countdown,
+    Interval,
+}
+
+// Modes that can be picked from the menu, in order
+const MENU: [Mode; 4] = [Mode::Counter, Mode::Stopwatch, Mode::Countdown, Mode::Interval];
+
+impl Mode {
+    // Short name shown on the menu
+    fn label(&self) -> &'static str {
+        match self {
+            Mode::Counter => "Cnt",
+            Mode::SetBrightness => "br",
+            Mode::Menu => "----",
+            Mode::Stopwatch => "StoP",
+            Mode::Countdown => "Cdn",
+            Mode::Interval => "Int",
+        }
+    }
+
+    fn step(&self, step: i32) -> Mode {
+        let index = MENU.iter().position(|mode| mode == self).unwrap_or(0) as i32;
+        MENU[(index + step).rem_euclid(MENU.len() as i32) as usize]
+    }
 }
 
 fn main() -> anyhow::Result<()> {
     esp_idf_sys::link_patches();
 
-    let mut counter: u16 = 0;
-    let mut brightness: u8 = 5;
+    // Restore the values from the last run
+    let mut settings = Settings::open(NvsStorage::new()?, "rotary-encoder", SETTINGS_VERSION)?;
+
+    let mut counter: u16 = settings.get(&COUNTER)?.min(MAX_COUNTER);
+    let mut brightness: u8 = settings.get(&BRIGHTNESS)?.min(MAX_BRIGHTNESS);
+    let mut selected_mode = Mode::Counter;
+    let mut in_menu = false;
     let mut current_mode;
 
+    let mut stopwatch = Stopwatch::new();
+    let mut lap: Option<(Duration, Instant)> = None;
+    let mut countdown = Countdown::new(Duration::from_secs(settings.get(&COUNTDOWN_SECONDS)?.min(MAX_TIMER_SECONDS) as u64));
+    let mut alarm_played = false;
+    let mut interval = Interval::new(Duration::from_secs(settings.get(&INTERVAL_SECONDS)?.min(MAX_TIMER_SECONDS) as u64));
+
     let peripherals = Peripherals::take().ok_or(CustomError::UnableToTakePeripherals)?;
 
     // For rotary encoder
@@ -60,6 +110,12 @@ fn main() -> anyhow::Result<()> {
     let dio_pin = peripherals.pins.gpio44;
     let clock_pin = peripherals.pins.gpio18;
 
+    // For buzzer
+    let sound = match BUZZER_ENABLED {
+        true => Some(Sound::new(peripherals.rmt.channel0, peripherals.pins.gpio2)?),
+        false => None,
+    };
+
     let rotary_dt = PinDriver::input(s1_pin)?;
     let rotary_clk = PinDriver::input(s2_pin)?;
     let button = PinDriver::input(key_pin)?;
@@ -72,103 +128,169 @@ fn main() -> anyhow::Result<()> {
     });
 
     let mut encoder = RotaryEncoder::new(rotary_dt, rotary_clk).into_standard_mode();
+    let started = Instant::now();
 
     // Setup display
-    let mut dio = PinDriver::input_output(dio_pin)?;
-    let mut clock = PinDriver::input_output(clock_pin)?;
-
-    let mut delay = FreeRtos;
-    let mut display = tm1637::TM1637::new(&mut clock, &mut dio, &mut delay);
-
-    display.init().map_err(|_| CustomError::FailedToInitializeDisplay)?;
-    display.clear().map_err(|_| CustomError::FailedToClearDisplay)?;
-    display.set_brightness(brightness).map_err(|_| CustomError::UnableToSetBrightness)?;
+    let mut display: SevenSegment = SegmentDisplay::new(clock_pin, dio_pin)?;
+    display.set_brightness(brightness)?;
 
     loop {
         button.tick();
         encoder.update();
 
-        // Reset counter when button is clicked
-        if button.is_clicked() {
-            counter = 0
+        let now = Instant::now();
+
+        // Double click opens the menu, a single click inside it picks the mode
+        if button.is_double_clicked() {
+            in_menu = !in_menu;
         }
 
         // Switch mode when button is held
-        current_mode = match button.raw_state().is_held() {
-            true => Mode::SetBrightness,
-            false => Mode::Counter
+        current_mode = match (button.raw_state().is_held(), in_menu) {
+            (true, _) => Mode::SetBrightness,
+            (false, true) => Mode::Menu,
+            (false, false) => selected_mode,
         };
 
-        handle_encoder(&encoder, &current_mode, &mut counter, &mut brightness);
+        let step = match encoder.direction() {
+            Direction::Clockwise => -1,
+            Direction::Anticlockwise => 1,
+            Direction::None => 0,
+        };
 
-        if current_mode == Mode::SetBrightness {
-            display.set_brightness(brightness).map_err(|_| CustomError::UnableToSetBrightness)?;
-            display.print_raw(0, &get_digits(brightness).as_slice()).map_err(|_| CustomError::UnableToPrint)?;
-        }
+        let clicked = button.is_clicked();
 
-        if current_mode == Mode::Counter {
-            display.print_raw(0, &get_digits(counter).as_slice())
-                .map_err(|_| CustomError::UnableToPrint)?;
-        }
+        match current_mode {
+            Mode::SetBrightness => {
+                brightness = (brightness as i32 + step).clamp(0, MAX_BRIGHTNESS as i32) as u8;
 
-        button.reset();
+                display.set_brightness(brightness)?;
+                display.print_integer(brightness as i32)?;
+            }
+            Mode::Menu => {
+                selected_mode = selected_mode.step(step);
 
-        FreeRtos::delay_ms(1);
-    }
-}
+                if clicked {
+                    in_menu = false;
+                }
 
-fn get_digits<Value>(value: Value) -> Vec<u8> where Value: Display {
-    let mut numbers: Vec<u8> = value
-        .to_string()
-        .chars()
-        .map(|d| d.to_digit(10).unwrap())
-        .map(|d| DIGITS[(d & 0xf) as usize])
-        .rev()
-        .collect();
+                display.print_text(selected_mode.label(), Align::Left)?;
+            }
+            Mode::Counter => {
+                // Reset counter when button is clicked
+                if clicked {
+                    counter = 0
+                }
 
-    numbers.resize(4, 0x00);
-    numbers.reverse();
+                counter = (counter as i32 + step).clamp(0, MAX_COUNTER as i32) as u16;
 
-    numbers
-}
+                display.print_integer(counter as i32)?;
+            }
+            Mode::Stopwatch => {
+                // Click starts and stops, turning records a lap while running and resets while stopped
+                if clicked {
+                    stopwatch.toggle(now);
+                }
 
-fn handle_encoder(
-    encoder: &RotaryEncoder<StandardMode, PinDriver<Gpio1, Input>, PinDriver<Gpio43, Input>>,
-    current_mode: &Mode,
-    counter: &mut u16,
-    brightness: &mut u8,
-) {
-    match encoder.direction() {
-        Direction::Clockwise => {
-            match current_mode {
-                Mode::Counter => {
-                    if *counter > 0 {
-                        *counter -= 1;
+                if step != 0 {
+                    match stopwatch.is_running() {
+                        true => lap = Some((stopwatch.lap(now), now)),
+                        false => {
+                            stopwatch.reset();
+                            lap = None;
+                        }
                     }
                 }
-                Mode::SetBrightness => {
-                    if *brightness > 0 {
-                        *brightness -= 1;
+
+                let shown = match lap {
+                    Some((time, recorded)) if now.duration_since(recorded) < LAP_DISPLAY => time,
+                    _ => stopwatch.elapsed(now),
+                };
+
+                print_duration(&mut display, shown.as_secs() as u32)?;
+            }
+            Mode::Countdown => {
+                // Click starts and pauses, once expired it goes back to the duration that was set
+                if clicked {
+                    match countdown.is_expired(now) {
+                        true => countdown.reset(),
+                        false => countdown.toggle(now),
                     }
                 }
+
+                if step != 0 && !countdown.is_running(now) {
+                    let seconds = step_seconds(countdown.duration(), step);
+                    countdown.set_duration(Duration::from_secs(seconds as u64));
+                    settings.set(&COUNTDOWN_SECONDS, seconds)?;
+                }
+
+                // Blink the zeros until acknowledged
+                match alarm_played && now.duration_since(started).as_millis() % 1000 >= 500 {
+                    true => display.clear()?,
+                    false => print_duration(&mut display, seconds_left(countdown.remaining(now)))?,
+                }
             }
-        }
-        Direction::Anticlockwise => {
-            match current_mode {
-                Mode::Counter => {
-                    if *counter < MAX_COUNTER {
-                        *counter += 1;
-                    }
+            Mode::Interval => {
+                // Click starts and stops, turning changes the period while stopped
+                if clicked {
+                    interval.toggle(now);
                 }
-                Mode::SetBrightness => {
-                    if *brightness < MAX_BRIGHTNESS {
-                        *brightness += 1;
-                    }
+
+                if step != 0 && !interval.is_running() {
+                    let seconds = step_seconds(interval.period(), step);
+                    interval.set_period(Duration::from_secs(seconds as u64));
+                    settings.set(&INTERVAL_SECONDS, seconds)?;
+                }
+
+                match interval.is_running() {
+                    true => print_duration(&mut display, seconds_left(interval.remaining(now)))?,
+                    false => print_duration(&mut display, interval.period().as_secs() as u32)?,
                 }
             }
         }
-        Direction::None => {
-            // Do nothing
+
+        // Timers keep going in every mode, so the buzzer is heard even from another one
+        match countdown.is_expired(now) && countdown.duration() > Duration::ZERO {
+            true if !alarm_played => {
+                alarm_played = true;
+                alert(&sound, Effect::Beep { frequency: 2000, duration: 1000 })?;
+            }
+            true => {}
+            false => alarm_played = false,
+        }
+
+        if interval.update(now) {
+            alert(&sound, Effect::Beep { frequency: 1500, duration: 200 })?;
         }
+
+        // Changes are only written to the flash once they settle
+        settings.set(&COUNTER, counter)?;
+        settings.set(&BRIGHTNESS, brightness)?;
+        settings.update()?;
+
+        button.reset();
+
+        FreeRtos::delay_ms(1);
     }
-}
\ No newline at end of file
+}
+
+// Shows seconds as mm:ss, switching to hh:mm once it no longer fits and stopping at 99:59 hours
+fn print_duration(display: &mut SevenSegment, seconds: u32) -> anyhow::Result<()> {
+    let seconds = seconds.min(MAX_DISPLAYED_SECONDS);
+
+    match seconds > MAX_TIMER_SECONDS {
+        true => display.print_hours_minutes(seconds / 60),
+        false => display.print_minutes_seconds(seconds),
+    }
+}
+
+fn step_seconds(duration: Duration, step: i32) -> u32 {
+    (duration.as_secs() as i32 + step * TIMER_STEP_SECONDS as i32).clamp(0, MAX_TIMER_SECONDS as i32) as u32
+}
+
+fn alert(sound: &Option<Sound>, effect: Effect) -> anyhow::Result<()> {
+    match sound {
+        Some(sound) => sound.play(effect),
+        None => Ok(()),
+    }
+}
@@ -0,0 +1,298 @@
+//! Timers for the stopwatch, countdown and interval modes. Every call that depends on the time takes
+//! the current `Instant`, so the main loop reads the clock once per pass and tests can pick the time.
+
+use std::time::{Duration, Instant};
+
+/// Counts up while running, it can be paused and resumed.
+#[derive(Debug, Default)]
+pub struct Stopwatch {
+    started: Option<Instant>,
+    // Time counted before the last pause
+    accumulated: Duration,
+    laps: Vec<Duration>,
+}
+
+impl Stopwatch {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.started.is_some()
+    }
+
+    /// Starts the stopwatch when it is paused and pauses it when it is running.
+    pub fn toggle(&mut self, now: Instant) {
+        match self.started.take() {
+            Some(started) => self.accumulated += now.saturating_duration_since(started),
+            None => self.started = Some(now),
+        }
+    }
+
+    pub fn elapsed(&self, now: Instant) -> Duration {
+        match self.started {
+            Some(started) => self.accumulated + now.saturating_duration_since(started),
+            None => self.accumulated,
+        }
+    }
+
+    /// Records a lap and returns how long it took since the previous one.
+    pub fn lap(&mut self, now: Instant) -> Duration {
+        let elapsed = self.elapsed(now);
+        let previous = self.laps.last().copied().unwrap_or_default();
+
+        self.laps.push(elapsed);
+
+        elapsed - previous
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::default();
+    }
+}
+
+/// Counts down from a duration and expires once it reaches zero.
+#[derive(Debug)]
+pub struct Countdown {
+    duration: Duration,
+    stopwatch: Stopwatch,
+}
+
+impl Countdown {
+    pub fn new(duration: Duration) -> Self {
+        Self { duration, stopwatch: Stopwatch::new() }
+    }
+
+    pub fn duration(&self) -> Duration {
+        self.duration
+    }
+
+    /// Changes the duration and starts over.
+    pub fn set_duration(&mut self, duration: Duration) {
+        self.duration = duration;
+        self.stopwatch.reset();
+    }
+
+    pub fn is_running(&self, now: Instant) -> bool {
+        self.stopwatch.is_running() && !self.is_expired(now)
+    }
+
+    pub fn toggle(&mut self, now: Instant) {
+        self.stopwatch.toggle(now);
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        self.duration.saturating_sub(self.stopwatch.elapsed(now))
+    }
+
+    pub fn is_expired(&self, now: Instant) -> bool {
+        self.remaining(now).is_zero()
+    }
+
+    pub fn reset(&mut self) {
+        self.stopwatch.reset();
+    }
+}
+
+/// Countdown that starts over every time it reaches zero, counting how many rounds went by.
+#[derive(Debug)]
+pub struct Interval {
+    period: Duration,
+    stopwatch: Stopwatch,
+    // Rounds already reported by update
+    reported: u32,
+}
+
+impl Interval {
+    pub fn new(period: Duration) -> Self {
+        Self { period, stopwatch: Stopwatch::new(), reported: 0 }
+    }
+
+    pub fn period(&self) -> Duration {
+        self.period
+    }
+
+    /// Changes the period and starts over.
+    pub fn set_period(&mut self, period: Duration) {
+        self.period = period;
+        self.reset();
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.stopwatch.is_running()
+    }
+
+    pub fn toggle(&mut self, now: Instant) {
+        self.stopwatch.toggle(now);
+    }
+
+    pub fn rounds(&self, now: Instant) -> u32 {
+        match self.period.is_zero() {
+            true => 0,
+            false => (self.stopwatch.elapsed(now).as_millis() / self.period.as_millis()) as u32,
+        }
+    }
+
+    pub fn remaining(&self, now: Instant) -> Duration {
+        match self.period.is_zero() {
+            true => Duration::ZERO,
+            false => {
+                let elapsed = self.stopwatch.elapsed(now).as_millis() % self.period.as_millis();
+                self.period - Duration::from_millis(elapsed as u64)
+            }
+        }
+    }
+
+    /// Returns true when a round ended since the last call.
+    pub fn update(&mut self, now: Instant) -> bool {
+        let rounds = self.rounds(now);
+
+        if rounds > self.reported {
+            self.reported = rounds;
+            return true;
+        }
+
+        false
+    }
+
+    pub fn reset(&mut self) {
+        self.stopwatch.reset();
+        self.reported = 0;
+    }
+}
+
+/// Whole seconds to show for a remaining duration, rounded up so zero only shows once the time is over.
+pub fn seconds_left(remaining: Duration) -> u32 {
+    ((remaining.as_millis() + 999) / 1000) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, Instant};
+
+    use crate::timer::{Countdown, Interval, seconds_left, Stopwatch};
+
+    fn at(start: Instant, milliseconds: u64) -> Instant {
+        start + Duration::from_millis(milliseconds)
+    }
+
+    #[test]
+    fn stopwatch_only_counts_while_running() {
+        let start = Instant::now();
+        let mut stopwatch = Stopwatch::new();
+
+        assert!(!stopwatch.is_running());
+        assert_eq!(stopwatch.elapsed(at(start, 100)), Duration::ZERO);
+
+        stopwatch.toggle(at(start, 100));
+        assert!(stopwatch.is_running());
+        assert_eq!(stopwatch.elapsed(at(start, 250)), Duration::from_millis(150));
+
+        stopwatch.toggle(at(start, 300));
+        assert!(!stopwatch.is_running());
+        assert_eq!(stopwatch.elapsed(at(start, 1_000)), Duration::from_millis(200));
+
+        stopwatch.toggle(at(start, 1_000));
+        assert_eq!(stopwatch.elapsed(at(start, 1_050)), Duration::from_millis(250));
+
+        stopwatch.reset();
+        assert!(!stopwatch.is_running());
+        assert_eq!(stopwatch.elapsed(at(start, 2_000)), Duration::ZERO);
+    }
+
+    #[test]
+    fn stopwatch_laps_are_relative_to_the_previous_one() {
+        let start = Instant::now();
+        let mut stopwatch = Stopwatch::new();
+        stopwatch.toggle(start);
+
+        assert_eq!(stopwatch.lap(at(start, 100)), Duration::from_millis(100));
+        assert_eq!(stopwatch.lap(at(start, 300)), Duration::from_millis(200));
+
+        // Time spent paused does not count towards the lap
+        stopwatch.toggle(at(start, 400));
+        stopwatch.toggle(at(start, 900));
+        assert_eq!(stopwatch.lap(at(start, 1_000)), Duration::from_millis(200));
+        assert_eq!(stopwatch.elapsed(at(start, 1_000)), Duration::from_millis(500));
+    }
+
+    #[test]
+    fn countdown_expires_at_zero() {
+        let start = Instant::now();
+        let mut countdown = Countdown::new(Duration::from_millis(200));
+
+        assert!(!countdown.is_running(start));
+        assert_eq!(countdown.remaining(at(start, 500)), Duration::from_millis(200));
+
+        countdown.toggle(start);
+        assert!(countdown.is_running(at(start, 50)));
+        assert!(!countdown.is_expired(at(start, 199)));
+        assert_eq!(countdown.remaining(at(start, 50)), Duration::from_millis(150));
+
+        assert!(countdown.is_expired(at(start, 200)));
+        assert!(!countdown.is_running(at(start, 200)));
+        assert_eq!(countdown.remaining(at(start, 300)), Duration::ZERO);
+
+        countdown.set_duration(Duration::from_millis(100));
+        assert!(!countdown.is_expired(at(start, 300)));
+        assert_eq!(countdown.remaining(at(start, 300)), Duration::from_millis(100));
+    }
+
+    #[test]
+    fn countdown_pauses() {
+        let start = Instant::now();
+        let mut countdown = Countdown::new(Duration::from_secs(10));
+
+        countdown.toggle(start);
+        countdown.toggle(at(start, 4_000));
+
+        assert!(!countdown.is_running(at(start, 60_000)));
+        assert_eq!(countdown.remaining(at(start, 60_000)), Duration::from_secs(6));
+
+        countdown.reset();
+        assert_eq!(countdown.remaining(at(start, 60_000)), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn interval_reports_each_round_once() {
+        let start = Instant::now();
+        let mut interval = Interval::new(Duration::from_millis(200));
+        interval.toggle(start);
+
+        assert!(!interval.update(at(start, 199)));
+        assert_eq!(interval.rounds(at(start, 199)), 0);
+        assert_eq!(interval.remaining(at(start, 150)), Duration::from_millis(50));
+
+        assert!(interval.update(at(start, 200)));
+        assert!(!interval.update(at(start, 250)));
+        assert_eq!(interval.rounds(at(start, 250)), 1);
+        assert_eq!(interval.remaining(at(start, 250)), Duration::from_millis(150));
+
+        // Rounds missed in between are reported together
+        assert!(interval.update(at(start, 1_000)));
+        assert_eq!(interval.rounds(at(start, 1_000)), 5);
+
+        interval.reset();
+        assert!(!interval.is_running());
+        assert_eq!(interval.rounds(at(start, 2_000)), 0);
+        assert_eq!(interval.remaining(at(start, 2_000)), Duration::from_millis(200));
+    }
+
+    #[test]
+    fn interval_without_a_period_never_ends() {
+        let start = Instant::now();
+        let mut interval = Interval::new(Duration::ZERO);
+        interval.toggle(start);
+
+        assert!(!interval.update(at(start, 1_000)));
+        assert_eq!(interval.remaining(at(start, 1_000)), Duration::ZERO);
+    }
+
+    #[test]
+    fn seconds_left_rounds_up() {
+        assert_eq!(seconds_left(Duration::ZERO), 0);
+        assert_eq!(seconds_left(Duration::from_millis(1)), 1);
+        assert_eq!(seconds_left(Duration::from_millis(1000)), 1);
+        assert_eq!(seconds_left(Duration::from_millis(1001)), 2);
+    }
+}
@@ -1,2 +1,14 @@
+#[cfg(feature = "esp")]
 pub mod tiny_display;
+#[cfg(feature = "esp")]
 pub mod rotary_encoder;
+#[cfg(feature = "esp")]
+pub mod sound;
+pub mod sound_effect;
+pub mod seven_segment;
+#[cfg(feature = "esp")]
+pub mod segment_display;
+pub mod segment_animation;
+pub mod settings;
+#[cfg(feature = "esp")]
+pub mod nvs_storage;
@@ -0,0 +1,88 @@
+use std::ffi::CString;
+
+use anyhow::anyhow;
+use esp_idf_sys::*;
+
+use crate::settings::Storage;
+
+/// Settings storage on the default NVS partition of the flash.
+pub struct NvsStorage {}
+
+impl NvsStorage {
+    /// Initializes the NVS partition, erasing it when it is full or was written by a newer ESP-IDF.
+    pub fn new() -> anyhow::Result<NvsStorage> {
+        unsafe {
+            let result = nvs_flash_init();
+
+            if result == ESP_ERR_NVS_NO_FREE_PAGES as esp_err_t || result == ESP_ERR_NVS_NEW_VERSION_FOUND as esp_err_t {
+                esp!(nvs_flash_erase())?;
+                esp!(nvs_flash_init())?;
+            } else {
+                esp!(result)?;
+            }
+        }
+
+        Ok(Self {})
+    }
+
+    // Runs the operation with the namespace open, closing it afterwards whatever the outcome
+    fn with_handle<T>(&self, namespace: &str, operation: impl FnOnce(nvs_handle_t) -> anyhow::Result<T>) -> anyhow::Result<T> {
+        let namespace = CString::new(namespace)?;
+        let mut handle: nvs_handle_t = 0;
+
+        unsafe {
+            esp!(nvs_open(namespace.as_ptr(), nvs_open_mode_t_NVS_READWRITE, &mut handle))
+                .map_err(|error| anyhow!("unable to open nvs namespace: {:?}", error))?;
+        }
+
+        let result = operation(handle);
+
+        unsafe {
+            nvs_close(handle);
+        }
+
+        result
+    }
+}
+
+impl Storage for NvsStorage {
+    fn read(&mut self, namespace: &str, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        let key = CString::new(key)?;
+
+        self.with_handle(namespace, |handle| unsafe {
+            let mut length: usize = 0;
+            let result = nvs_get_blob(handle, key.as_ptr(), std::ptr::null_mut(), &mut length);
+
+            if result == ESP_ERR_NVS_NOT_FOUND as esp_err_t {
+                return Ok(None);
+            }
+
+            esp!(result)?;
+
+            let mut value = vec![0u8; length];
+            esp!(nvs_get_blob(handle, key.as_ptr(), value.as_mut_ptr() as *mut _, &mut length))?;
+
+            Ok(Some(value))
+        })
+    }
+
+    fn write(&mut self, namespace: &str, key: &str, value: &[u8]) -> anyhow::Result<()> {
+        let key = CString::new(key)?;
+
+        self.with_handle(namespace, |handle| unsafe {
+            esp!(nvs_set_blob(handle, key.as_ptr(), value.as_ptr() as *const _, value.len()))?;
+            esp!(nvs_commit(handle))?;
+
+            Ok(())
+        })
+    }
+
+    fn erase(&mut self, namespace: &str) -> anyhow::Result<()> {
+        self.with_handle(namespace, |handle| unsafe {
+            esp!(nvs_erase_all(handle))?;
+            esp!(nvs_commit(handle))?;
+
+            Ok(())
+        })
+    }
+}
@@ -0,0 +1,326 @@
+//! Animations for the seven-segment display. The frames are plain functions of the elapsed time, the
+//! `SegmentAnimator` plays them on the device.
+//!
+//! The animator runs on its own thread rather than on the ESP timer service: timer callbacks share a
+//! single task and must not block, while the TM1637 driver waits with `FreeRtos` delays between every
+//! bit it sends. The thread sleeps in `recv_timeout` between frames, so it only wakes up to draw.
+
+#[cfg(feature = "esp")]
+use std::sync::{Arc, mpsc, Mutex};
+#[cfg(feature = "esp")]
+use std::sync::mpsc::{RecvTimeoutError, Sender};
+#[cfg(feature = "esp")]
+use std::thread::spawn;
+#[cfg(feature = "esp")]
+use std::time::{Duration, Instant};
+
+#[cfg(feature = "esp")]
+use anyhow::anyhow;
+#[cfg(feature = "esp")]
+use esp_idf_hal::gpio::{InputPin, OutputPin};
+
+#[cfg(feature = "esp")]
+use crate::segment_display::SegmentDisplay;
+use crate::seven_segment::{BLANK, DIGIT_COUNT, Digits, MAX_BRIGHTNESS, segments, SEGMENT_A, SEGMENT_B, SEGMENT_C, SEGMENT_D, SEGMENT_E, SEGMENT_F};
+
+// How often the animation thread checks whether the frame has changed
+#[cfg(feature = "esp")]
+const FRAME_MS: u64 = 20;
+
+pub const DEFAULT_MARQUEE_STEP_MS: u32 = 300;
+pub const DEFAULT_SPINNER_STEP_MS: u32 = 80;
+
+// A single segment running around the outer edge of the display, clockwise from the top left
+const SPINNER: [(usize, u8); 12] = [
+    (0, SEGMENT_A), (1, SEGMENT_A), (2, SEGMENT_A), (3, SEGMENT_A),
+    (3, SEGMENT_B), (3, SEGMENT_C),
+    (3, SEGMENT_D), (2, SEGMENT_D), (1, SEGMENT_D), (0, SEGMENT_D),
+    (0, SEGMENT_E), (0, SEGMENT_F),
+];
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Animation {
+    Static(Digits),
+    // Scrolls the glyphs in from the right, one digit every step
+    Marquee { glyphs: Vec<u8>, step: u32, repeat: bool },
+    Spinner { step: u32 },
+    // Alternates between the digits and a blank display
+    Blink { digits: Digits, interval: u32 },
+    // Ramps the brightness down and back up over the period
+    Fade { digits: Digits, period: u32 },
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Frame {
+    pub digits: Digits,
+    pub brightness: u8,
+}
+
+impl Animation {
+    /// Builds a marquee from text, which can be of any length.
+    pub fn marquee(text: &str, step: u32, repeat: bool) -> anyhow::Result<Animation> {
+        Ok(Animation::Marquee { glyphs: segments(text)?, step, repeat })
+    }
+
+    /// Returns what should be on the display once the given amount of milliseconds has passed.
+    pub fn frame(&self, elapsed: u32, brightness: u8) -> Frame {
+        let digits = match self {
+            Animation::Static(digits) => *digits,
+            Animation::Marquee { glyphs, step, repeat } => {
+                // The text starts off screen on the right and leaves on the left
+                let length = glyphs.len() + DIGIT_COUNT;
+                let mut position = (elapsed / (*step).max(1)) as usize;
+
+                position = match repeat {
+                    true => position % length,
+                    false => position.min(length),
+                };
+
+                let mut digits = [BLANK; DIGIT_COUNT];
+
+                for (index, digit) in digits.iter_mut().enumerate() {
+                    if let Some(glyph) = (position + index).checked_sub(DIGIT_COUNT).and_then(|index| glyphs.get(index)) {
+                        *digit = *glyph;
+                    }
+                }
+
+                digits
+            }
+            Animation::Spinner { step } => {
+                let (index, segment) = SPINNER[(elapsed / (*step).max(1)) as usize % SPINNER.len()];
+                let mut digits = [BLANK; DIGIT_COUNT];
+                digits[index] = segment;
+                digits
+            }
+            Animation::Blink { digits, interval } => match (elapsed / (*interval).max(1)) % 2 {
+                0 => *digits,
+                _ => [BLANK; DIGIT_COUNT],
+            },
+            Animation::Fade { digits, .. } => *digits,
+        };
+
+        let brightness = match self {
+            Animation::Fade { period, .. } => {
+                let half = ((*period).max(2) / 2) as i64;
+                let progress = (elapsed as i64 % (half * 2) - half).abs();
+                (brightness.min(MAX_BRIGHTNESS) as i64 * progress / half) as u8
+            }
+            _ => brightness,
+        };
+
+        Frame { digits, brightness }
+    }
+}
+
+#[cfg(feature = "esp")]
+#[derive(Debug, Clone, PartialEq)]
+enum Command {
+    Play(Animation),
+    Brightness(u8),
+}
+
+/// Drives a [`SegmentDisplay`] from a background thread so animations keep running without the main loop.
+/// Everything shown on the display goes through here, a new animation replaces the current one.
+/// If the display fails to update, the error is returned by the next command.
+#[cfg(feature = "esp")]
+pub struct SegmentAnimator {
+    sender: Sender<Command>,
+    failure: Arc<Mutex<Option<anyhow::Error>>>,
+}
+
+#[cfg(feature = "esp")]
+impl SegmentAnimator {
+    pub fn new<CLK, DIO>(display: SegmentDisplay<'static, CLK, DIO>, brightness: u8) -> SegmentAnimator
+    where
+        CLK: InputPin + OutputPin + Send,
+        DIO: InputPin + OutputPin + Send,
+    {
+        let (sender, receiver) = mpsc::channel();
+        let failure = Arc::new(Mutex::new(None));
+        let thread_failure = failure.clone();
+
+        spawn(move || {
+            let mut display = display;
+            let mut animation = Animation::Static([BLANK; DIGIT_COUNT]);
+            let mut brightness = brightness.min(MAX_BRIGHTNESS);
+            let mut started = Instant::now();
+            let mut shown: Option<Frame> = None;
+
+            loop {
+                match receiver.recv_timeout(Duration::from_millis(FRAME_MS)) {
+                    Ok(Command::Play(next)) => {
+                        animation = next;
+                        started = Instant::now();
+                    }
+                    Ok(Command::Brightness(level)) => brightness = level.min(MAX_BRIGHTNESS),
+                    Err(RecvTimeoutError::Timeout) => {}
+                    Err(RecvTimeoutError::Disconnected) => break,
+                }
+
+                let frame = animation.frame(started.elapsed().as_millis() as u32, brightness);
+
+                // Only talk to the display when something changed
+                if shown != Some(frame) {
+                    // Forgetting the frame makes the next one redraw everything after a failure
+                    shown = match show(&mut display, &frame, shown.as_ref()) {
+                        Ok(()) => Some(frame),
+                        Err(error) => {
+                            *thread_failure.lock().unwrap() = Some(error);
+                            None
+                        }
+                    };
+                }
+            }
+        });
+
+        Self { sender, failure }
+    }
+
+    pub fn play(&self, animation: Animation) -> anyhow::Result<()> {
+        self.send(Command::Play(animation))
+    }
+
+    pub fn set_brightness(&self, level: u8) -> anyhow::Result<()> {
+        self.send(Command::Brightness(level))
+    }
+
+    pub fn show(&self, digits: Digits) -> anyhow::Result<()> {
+        self.play(Animation::Static(digits))
+    }
+
+    pub fn clear(&self) -> anyhow::Result<()> {
+        self.show([BLANK; DIGIT_COUNT])
+    }
+
+    pub fn marquee(&self, text: &str, step: u32) -> anyhow::Result<()> {
+        self.play(Animation::marquee(text, step, true)?)
+    }
+
+    pub fn spinner(&self) -> anyhow::Result<()> {
+        self.play(Animation::Spinner { step: DEFAULT_SPINNER_STEP_MS })
+    }
+
+    pub fn blink(&self, digits: Digits, interval: u32) -> anyhow::Result<()> {
+        self.play(Animation::Blink { digits, interval })
+    }
+
+    pub fn fade(&self, digits: Digits, period: u32) -> anyhow::Result<()> {
+        self.play(Animation::Fade { digits, period })
+    }
+
+    fn send(&self, command: Command) -> anyhow::Result<()> {
+        self.sender
+            .send(command)
+            .map_err(|error| anyhow!("display thread is not running: {:?}", error))?;
+
+        match self.failure.lock().unwrap().take() {
+            Some(error) => Err(error.context("failed to update the display")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[cfg(feature = "esp")]
+fn show<CLK, DIO>(display: &mut SegmentDisplay<'static, CLK, DIO>, frame: &Frame, previous: Option<&Frame>) -> anyhow::Result<()>
+where
+    CLK: InputPin + OutputPin,
+    DIO: InputPin + OutputPin,
+{
+    if previous.map(|previous| previous.brightness) != Some(frame.brightness) {
+        display.set_brightness(frame.brightness)?;
+    }
+
+    if previous.map(|previous| previous.digits) != Some(frame.digits) {
+        display.print_digits(&frame.digits)?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::segment_animation::{Animation, Frame};
+    use crate::seven_segment::{BLANK, MAX_BRIGHTNESS, SEGMENT_A, SEGMENT_B, SEGMENT_D, SEGMENT_F};
+
+    const DIGITS: [u8; 4] = [1, 2, 3, 4];
+
+    fn digits(animation: &Animation, elapsed: u32) -> [u8; 4] {
+        animation.frame(elapsed, MAX_BRIGHTNESS).digits
+    }
+
+    #[test]
+    fn static_digits_never_change() {
+        let animation = Animation::Static(DIGITS);
+
+        assert_eq!(animation.frame(0, 3), Frame { digits: DIGITS, brightness: 3 });
+        assert_eq!(animation.frame(60_000, 3), Frame { digits: DIGITS, brightness: 3 });
+    }
+
+    #[test]
+    fn marquee_scrolls_in_from_the_right() {
+        let animation = Animation::Marquee { glyphs: vec![7, 8], step: 100, repeat: false };
+
+        assert_eq!(digits(&animation, 0), [BLANK; 4]);
+        assert_eq!(digits(&animation, 99), [BLANK; 4]);
+        assert_eq!(digits(&animation, 100), [BLANK, BLANK, BLANK, 7]);
+        assert_eq!(digits(&animation, 250), [BLANK, BLANK, 7, 8]);
+        assert_eq!(digits(&animation, 400), [7, 8, BLANK, BLANK]);
+        assert_eq!(digits(&animation, 500), [8, BLANK, BLANK, BLANK]);
+    }
+
+    #[test]
+    fn marquee_stays_blank_or_starts_over_at_the_end() {
+        let once = Animation::Marquee { glyphs: vec![7, 8], step: 100, repeat: false };
+        let repeated = Animation::Marquee { glyphs: vec![7, 8], step: 100, repeat: true };
+
+        assert_eq!(digits(&once, 600), [BLANK; 4]);
+        assert_eq!(digits(&once, 10_000), [BLANK; 4]);
+
+        assert_eq!(digits(&repeated, 600), [BLANK; 4]);
+        assert_eq!(digits(&repeated, 700), [BLANK, BLANK, BLANK, 7]);
+        assert_eq!(digits(&repeated, 1_000), [7, 8, BLANK, BLANK]);
+    }
+
+    #[test]
+    fn spinner_runs_clockwise_around_the_edge() {
+        let animation = Animation::Spinner { step: 80 };
+
+        assert_eq!(digits(&animation, 0), [SEGMENT_A, BLANK, BLANK, BLANK]);
+        assert_eq!(digits(&animation, 3 * 80), [BLANK, BLANK, BLANK, SEGMENT_A]);
+        assert_eq!(digits(&animation, 4 * 80), [BLANK, BLANK, BLANK, SEGMENT_B]);
+        assert_eq!(digits(&animation, 9 * 80), [SEGMENT_D, BLANK, BLANK, BLANK]);
+        assert_eq!(digits(&animation, 11 * 80), [SEGMENT_F, BLANK, BLANK, BLANK]);
+        assert_eq!(digits(&animation, 12 * 80), [SEGMENT_A, BLANK, BLANK, BLANK]);
+    }
+
+    #[test]
+    fn blink_alternates_with_a_blank_display() {
+        let animation = Animation::Blink { digits: DIGITS, interval: 500 };
+
+        assert_eq!(digits(&animation, 0), DIGITS);
+        assert_eq!(digits(&animation, 499), DIGITS);
+        assert_eq!(digits(&animation, 500), [BLANK; 4]);
+        assert_eq!(digits(&animation, 1_000), DIGITS);
+    }
+
+    #[test]
+    fn fade_ramps_the_brightness_down_and_up() {
+        let animation = Animation::Fade { digits: DIGITS, period: 1_000 };
+        let brightness = |elapsed| animation.frame(elapsed, MAX_BRIGHTNESS).brightness;
+
+        assert_eq!(brightness(0), MAX_BRIGHTNESS);
+        assert_eq!(brightness(250), 3);
+        assert_eq!(brightness(500), 0);
+        assert_eq!(brightness(750), 3);
+        assert_eq!(brightness(1_000), MAX_BRIGHTNESS);
+        assert_eq!(digits(&animation, 500), DIGITS);
+    }
+
+    #[test]
+    fn fade_is_limited_to_the_display_brightness() {
+        let animation = Animation::Fade { digits: DIGITS, period: 1_000 };
+
+        assert_eq!(animation.frame(0, 100).brightness, MAX_BRIGHTNESS);
+        assert_eq!(animation.frame(0, 2).brightness, 2);
+    }
+}
@@ -0,0 +1,77 @@
+use anyhow::anyhow;
+use esp_idf_hal::delay::FreeRtos;
+use esp_idf_hal::gpio::{InputOutput, InputPin, OutputPin, PinDriver};
+use esp_idf_hal::peripheral::Peripheral;
+use tm1637::TM1637;
+
+pub use crate::seven_segment::MAX_BRIGHTNESS;
+use crate::seven_segment::{Align, Digits, format, format_fixed, format_hours_minutes, format_integer, format_minutes_seconds, format_temperature};
+
+/// 4-digit TM1637 seven-segment display.
+/// The driver only borrows its pins, so it is created for every command instead of being kept around.
+pub struct SegmentDisplay<'d, CLK: InputPin + OutputPin, DIO: InputPin + OutputPin> {
+    clock: PinDriver<'d, CLK, InputOutput>,
+    dio: PinDriver<'d, DIO, InputOutput>,
+    delay: FreeRtos,
+}
+
+impl<'d, CLK: InputPin + OutputPin, DIO: InputPin + OutputPin> SegmentDisplay<'d, CLK, DIO> {
+    pub fn new(
+        clock: impl Peripheral<P=CLK> + 'd,
+        dio: impl Peripheral<P=DIO> + 'd,
+    ) -> anyhow::Result<SegmentDisplay<'d, CLK, DIO>> {
+        let mut display = Self {
+            clock: PinDriver::input_output(clock)?,
+            dio: PinDriver::input_output(dio)?,
+            delay: FreeRtos,
+        };
+
+        display.device().init().map_err(|_| anyhow!("unable to initialize display"))?;
+        display.clear()?;
+
+        Ok(display)
+    }
+
+    fn device(&mut self) -> TM1637<'_, PinDriver<'d, CLK, InputOutput>, PinDriver<'d, DIO, InputOutput>, FreeRtos> {
+        TM1637::new(&mut self.clock, &mut self.dio, &mut self.delay)
+    }
+
+    pub fn clear(&mut self) -> anyhow::Result<()> {
+        self.device().clear().map_err(|_| anyhow!("failed to clear display"))
+    }
+
+    /// Sets the brightness from 0 up to [`MAX_BRIGHTNESS`].
+    pub fn set_brightness(&mut self, level: u8) -> anyhow::Result<()> {
+        self.device()
+            .set_brightness(level.min(MAX_BRIGHTNESS))
+            .map_err(|_| anyhow!("failed to set display brightness"))
+    }
+
+    pub fn print_digits(&mut self, digits: &Digits) -> anyhow::Result<()> {
+        self.device().print_raw(0, digits).map_err(|_| anyhow!("failed to print to the display"))
+    }
+
+    pub fn print_text(&mut self, text: &str, align: Align) -> anyhow::Result<()> {
+        self.print_digits(&format(text, align)?)
+    }
+
+    pub fn print_integer(&mut self, value: i32) -> anyhow::Result<()> {
+        self.print_digits(&format_integer(value)?)
+    }
+
+    pub fn print_fixed(&mut self, value: f32, decimals: usize) -> anyhow::Result<()> {
+        self.print_digits(&format_fixed(value, decimals)?)
+    }
+
+    pub fn print_minutes_seconds(&mut self, total_seconds: u32) -> anyhow::Result<()> {
+        self.print_digits(&format_minutes_seconds(total_seconds)?)
+    }
+
+    pub fn print_hours_minutes(&mut self, total_minutes: u32) -> anyhow::Result<()> {
+        self.print_digits(&format_hours_minutes(total_minutes)?)
+    }
+
+    pub fn print_temperature(&mut self, value: i32, unit: char) -> anyhow::Result<()> {
+        self.print_digits(&format_temperature(value, unit)?)
+    }
+}
@@ -0,0 +1,351 @@
+//! Typed settings kept in a key-value storage, such as the NVS partition of the flash.
+//!
+//! Each app opens its own namespace with a version number, bumping the version discards
+//! whatever was stored by an older layout. Changes are held in memory and only written once
+//! they have settled for a while, so turning an encoder doesn't wear the flash out.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use anyhow::anyhow;
+
+// Names are limited to 15 characters by NVS
+pub const MAX_NAME_LENGTH: usize = 15;
+
+pub const DEFAULT_DEBOUNCE: Duration = Duration::from_secs(2);
+
+const VERSION_KEY: &str = "version";
+
+pub trait Storage {
+    fn read(&mut self, namespace: &str, key: &str) -> anyhow::Result<Option<Vec<u8>>>;
+
+    fn write(&mut self, namespace: &str, key: &str, value: &[u8]) -> anyhow::Result<()>;
+
+    /// Removes every key of the namespace.
+    fn erase(&mut self, namespace: &str) -> anyhow::Result<()>;
+}
+
+/// Keeps everything in memory, for running the settings logic on the host.
+#[derive(Debug, Default, Clone)]
+pub struct MemoryStorage {
+    values: HashMap<(String, String), Vec<u8>>,
+    pub reads: usize,
+    pub writes: usize,
+}
+
+impl MemoryStorage {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn read(&mut self, namespace: &str, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+        self.reads += 1;
+        Ok(self.values.get(&(namespace.to_string(), key.to_string())).cloned())
+    }
+
+    fn write(&mut self, namespace: &str, key: &str, value: &[u8]) -> anyhow::Result<()> {
+        self.writes += 1;
+        self.values.insert((namespace.to_string(), key.to_string()), value.to_vec());
+
+        Ok(())
+    }
+
+    fn erase(&mut self, namespace: &str) -> anyhow::Result<()> {
+        self.values.retain(|(key_namespace, _), _| key_namespace != namespace);
+
+        Ok(())
+    }
+}
+
+/// A value that can be stored as bytes.
+pub trait Value: Sized + Copy + PartialEq {
+    fn encode(&self) -> Vec<u8>;
+
+    /// Returns None when the bytes don't hold a valid value, the default is used then.
+    fn decode(bytes: &[u8]) -> Option<Self>;
+}
+
+macro_rules! impl_value {
+    ($($type:ty),*) => {
+        $(
+            impl Value for $type {
+                fn encode(&self) -> Vec<u8> {
+                    self.to_le_bytes().to_vec()
+                }
+
+                fn decode(bytes: &[u8]) -> Option<Self> {
+                    Some(<$type>::from_le_bytes(bytes.try_into().ok()?))
+                }
+            }
+        )*
+    };
+}
+
+impl_value!(u8, u16, u32, u64, i8, i16, i32, i64, f32);
+
+impl Value for bool {
+    fn encode(&self) -> Vec<u8> {
+        vec![*self as u8]
+    }
+
+    fn decode(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            [0] => Some(false),
+            [1] => Some(true),
+            _ => None,
+        }
+    }
+}
+
+/// Name and default value of a setting.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Key<T: Value> {
+    pub name: &'static str,
+    pub default: T,
+}
+
+impl<T: Value> Key<T> {
+    pub const fn new(name: &'static str, default: T) -> Self {
+        Self { name, default }
+    }
+}
+
+pub struct Settings<S: Storage> {
+    storage: S,
+    namespace: &'static str,
+    debounce: Duration,
+    // Values waiting to be written along with when they last changed
+    pending: HashMap<&'static str, (Vec<u8>, Instant)>,
+    // Bytes last read from or written to the storage, None when the key isn't stored
+    stored: HashMap<&'static str, Option<Vec<u8>>>,
+}
+
+impl<S: Storage> Settings<S> {
+    /// Opens a namespace, erasing it first when it was written with a different version.
+    pub fn open(mut storage: S, namespace: &'static str, version: u32) -> anyhow::Result<Settings<S>> {
+        if namespace.len() > MAX_NAME_LENGTH {
+            return Err(anyhow!("namespace {:?} is longer than {} characters", namespace, MAX_NAME_LENGTH));
+        }
+
+        let stored = storage.read(namespace, VERSION_KEY)?.and_then(|bytes| u32::decode(&bytes));
+
+        if stored != Some(version) {
+            storage.erase(namespace)?;
+            storage.write(namespace, VERSION_KEY, &version.encode())?;
+        }
+
+        Ok(Self { storage, namespace, debounce: DEFAULT_DEBOUNCE, pending: HashMap::new(), stored: HashMap::new() })
+    }
+
+    /// How long a value must stay unchanged before it is written.
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    pub fn storage(&self) -> &S {
+        &self.storage
+    }
+
+    /// Returns the latest value of the setting, or its default when it was never saved.
+    pub fn get<T: Value>(&mut self, key: &Key<T>) -> anyhow::Result<T> {
+        match self.pending.get(key.name).and_then(|(bytes, _)| T::decode(bytes)) {
+            Some(value) => Ok(value),
+            None => self.get_stored(key),
+        }
+    }
+
+    /// Queues the value to be written once it settles, nothing happens if it didn't change.
+    /// Setting the stored value back drops the pending one.
+    pub fn set<T: Value>(&mut self, key: &Key<T>, value: T) -> anyhow::Result<()> {
+        if key.name.len() > MAX_NAME_LENGTH || key.name == VERSION_KEY {
+            return Err(anyhow!("{:?} can't be used as a setting name", key.name));
+        }
+
+        if self.get_stored(key)? == value {
+            self.pending.remove(key.name);
+        } else if self.get(key)? != value {
+            self.pending.insert(key.name, (value.encode(), Instant::now()));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the values that haven't changed for the debounce duration, meant to be called from the main loop.
+    pub fn update(&mut self) -> anyhow::Result<()> {
+        let debounce = self.debounce;
+        self.write_pending(|changed| changed.elapsed() >= debounce)
+    }
+
+    /// Writes every pending value right away.
+    pub fn flush(&mut self) -> anyhow::Result<()> {
+        self.write_pending(|_| true)
+    }
+
+    pub fn has_pending(&self) -> bool {
+        !self.pending.is_empty()
+    }
+
+    fn write_pending(&mut self, due: impl Fn(&Instant) -> bool) -> anyhow::Result<()> {
+        let keys: Vec<&'static str> = self.pending
+            .iter()
+            .filter(|(_, (_, changed))| due(changed))
+            .map(|(key, _)| *key)
+            .collect();
+
+        for key in keys {
+            if let Some((bytes, _)) = self.pending.remove(key) {
+                self.storage.write(self.namespace, key, &bytes)?;
+                self.stored.insert(key, Some(bytes));
+            }
+        }
+
+        Ok(())
+    }
+
+    // Only the first call for each key reads the storage, the main loop sets every setting on each iteration
+    fn get_stored<T: Value>(&mut self, key: &Key<T>) -> anyhow::Result<T> {
+        let bytes = match self.stored.get(key.name) {
+            Some(bytes) => bytes.clone(),
+            None => {
+                let bytes = self.storage.read(self.namespace, key.name)?;
+                self.stored.insert(key.name, bytes.clone());
+                bytes
+            }
+        };
+
+        Ok(bytes.and_then(|bytes| T::decode(&bytes)).unwrap_or(key.default))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread::sleep;
+    use std::time::Duration;
+
+    use anyhow::anyhow;
+
+    use crate::settings::{Key, MemoryStorage, Settings, Storage, VERSION_KEY};
+
+    const NAMESPACE: &str = "test";
+    const COUNTER: Key<u16> = Key::new("counter", 7);
+    const ENABLED: Key<bool> = Key::new("enabled", false);
+
+    // Every settings namespace starts with its version being written
+    const VERSION_WRITES: usize = 1;
+
+    // Opens fine but fails to read any setting
+    struct BrokenStorage;
+
+    impl Storage for BrokenStorage {
+        fn read(&mut self, _namespace: &str, key: &str) -> anyhow::Result<Option<Vec<u8>>> {
+            match key {
+                VERSION_KEY => Ok(None),
+                _ => Err(anyhow!("flash read failed")),
+            }
+        }
+
+        fn write(&mut self, _namespace: &str, _key: &str, _value: &[u8]) -> anyhow::Result<()> {
+            Ok(())
+        }
+
+        fn erase(&mut self, _namespace: &str) -> anyhow::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn writes_once_the_value_settles() {
+        let mut settings = Settings::open(MemoryStorage::new(), NAMESPACE, 1).unwrap()
+            .with_debounce(Duration::from_millis(50));
+
+        settings.set(&COUNTER, 1).unwrap();
+        settings.set(&COUNTER, 2).unwrap();
+        settings.update().unwrap();
+
+        assert_eq!(settings.get(&COUNTER).unwrap(), 2);
+        assert!(settings.has_pending());
+        assert_eq!(settings.storage().writes, VERSION_WRITES);
+
+        sleep(Duration::from_millis(60));
+        settings.update().unwrap();
+
+        assert!(!settings.has_pending());
+        assert_eq!(settings.storage().writes, VERSION_WRITES + 1);
+
+        let mut reopened = Settings::open(settings.storage().clone(), NAMESPACE, 1).unwrap();
+        assert_eq!(reopened.get(&COUNTER).unwrap(), 2);
+    }
+
+    #[test]
+    fn unchanged_values_are_not_written() {
+        let mut settings = Settings::open(MemoryStorage::new(), NAMESPACE, 1).unwrap();
+
+        // Defaults are never written
+        settings.set(&COUNTER, 7).unwrap();
+        settings.set(&ENABLED, false).unwrap();
+        assert!(!settings.has_pending());
+
+        settings.set(&COUNTER, 3).unwrap();
+        settings.flush().unwrap();
+        assert_eq!(settings.storage().writes, VERSION_WRITES + 1);
+
+        // Going back to the stored value drops the pending change
+        settings.set(&COUNTER, 4).unwrap();
+        settings.set(&COUNTER, 3).unwrap();
+        assert!(!settings.has_pending());
+
+        settings.flush().unwrap();
+        assert_eq!(settings.storage().writes, VERSION_WRITES + 1);
+    }
+
+    #[test]
+    fn storage_is_read_once_per_key() {
+        let mut settings = Settings::open(MemoryStorage::new(), NAMESPACE, 1).unwrap();
+        let reads = settings.storage().reads;
+
+        for _ in 0..100 {
+            settings.set(&COUNTER, 7).unwrap();
+            settings.set(&ENABLED, true).unwrap();
+        }
+
+        settings.flush().unwrap();
+        assert!(settings.get(&ENABLED).unwrap());
+        assert_eq!(settings.storage().reads, reads + 2);
+    }
+
+    #[test]
+    fn version_mismatch_erases_the_namespace() {
+        let mut settings = Settings::open(MemoryStorage::new(), NAMESPACE, 1).unwrap();
+        settings.set(&COUNTER, 3).unwrap();
+        settings.flush().unwrap();
+
+        let mut same = Settings::open(settings.storage().clone(), NAMESPACE, 1).unwrap();
+        assert_eq!(same.get(&COUNTER).unwrap(), 3);
+
+        let mut newer = Settings::open(settings.storage().clone(), NAMESPACE, 2).unwrap();
+        assert_eq!(newer.get(&COUNTER).unwrap(), COUNTER.default);
+
+        let mut other = Settings::open(settings.storage().clone(), "other", 2).unwrap();
+        assert_eq!(other.get(&COUNTER).unwrap(), COUNTER.default);
+    }
+
+    #[test]
+    fn reports_storage_errors() {
+        let mut settings = Settings::open(BrokenStorage, NAMESPACE, 1).unwrap();
+
+        assert!(settings.get(&COUNTER).is_err());
+        assert!(settings.set(&COUNTER, 3).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_names() {
+        let mut settings = Settings::open(MemoryStorage::new(), NAMESPACE, 1).unwrap();
+
+        assert!(settings.set(&Key::new("version", 1u8), 2).is_err());
+        assert!(settings.set(&Key::new("a-very-long-setting", 1u8), 2).is_err());
+        assert!(Settings::open(MemoryStorage::new(), "a-very-long-namespace", 1).is_err());
+    }
+}
@@ -0,0 +1,255 @@
+use std::fmt::{Display, Formatter};
+
+// Number of digits on the TM1637 modules we use
+pub const DIGIT_COUNT: usize = 4;
+
+pub type Digits = [u8; DIGIT_COUNT];
+
+// Brightness levels go from 0 up to this
+pub const MAX_BRIGHTNESS: u8 = 7;
+
+// Segment bits, A is the top segment and they go clockwise up to F, G is the middle one
+pub const SEGMENT_A: u8 = 0b0000_0001;
+pub const SEGMENT_B: u8 = 0b0000_0010;
+pub const SEGMENT_C: u8 = 0b0000_0100;
+pub const SEGMENT_D: u8 = 0b0000_1000;
+pub const SEGMENT_E: u8 = 0b0001_0000;
+pub const SEGMENT_F: u8 = 0b0010_0000;
+pub const SEGMENT_G: u8 = 0b0100_0000;
+// Decimal point, on clock modules this bit of the second digit drives the colon instead
+pub const SEGMENT_DP: u8 = 0b1000_0000;
+
+pub const COLON_DIGIT: usize = 1;
+
+pub const BLANK: u8 = 0x00;
+pub const MINUS: u8 = SEGMENT_G;
+pub const UNDERSCORE: u8 = SEGMENT_D;
+pub const DEGREE: u8 = SEGMENT_A | SEGMENT_B | SEGMENT_F | SEGMENT_G;
+
+// Hex digits from 0 to F
+pub const HEX_DIGITS: [u8; 16] = [
+    0x3f, 0x06, 0x5b, 0x4f,
+    0x66, 0x6d, 0x7d, 0x07,
+    0x7f, 0x6f, 0x77, 0x7c,
+    0x39, 0x5e, 0x79, 0x71,
+];
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Align {
+    Left,
+    Right,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum FormatError {
+    // The text needs more digits than the display has
+    Overflow { required: usize },
+    UnsupportedCharacter(char),
+}
+
+impl std::error::Error for FormatError {}
+
+impl Display for FormatError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            FormatError::Overflow { required } => write!(f, "Error: {} digits required but only {} available", required, DIGIT_COUNT),
+            FormatError::UnsupportedCharacter(char) => write!(f, "Error: {:?} can't be shown on a seven-segment display", char),
+        }
+    }
+}
+
+/// Returns the segments for a character, letters that only exist in one case fall back to the other one.
+pub fn glyph(char: char) -> Option<u8> {
+    exact_glyph(char)
+        .or_else(|| exact_glyph(char.to_ascii_lowercase()))
+        .or_else(|| exact_glyph(char.to_ascii_uppercase()))
+}
+
+fn exact_glyph(char: char) -> Option<u8> {
+    let segments = match char {
+        '0'..='9' => HEX_DIGITS[char as usize - '0' as usize],
+        'A' => 0x77,
+        'b' => 0x7c,
+        'C' => 0x39,
+        'c' => 0x58,
+        'd' => 0x5e,
+        'E' => 0x79,
+        'F' => 0x71,
+        'G' => 0x3d,
+        'H' => 0x76,
+        'h' => 0x74,
+        'I' => 0x30,
+        'i' => 0x10,
+        'J' => 0x1e,
+        'L' => 0x38,
+        'n' => 0x54,
+        'O' => 0x3f,
+        'o' => 0x5c,
+        'P' => 0x73,
+        'q' => 0x67,
+        'r' => 0x50,
+        'S' => 0x6d,
+        't' => 0x78,
+        'U' => 0x3e,
+        'u' => 0x1c,
+        'y' => 0x6e,
+        '-' => MINUS,
+        '_' => UNDERSCORE,
+        '°' => DEGREE,
+        '=' => SEGMENT_D | SEGMENT_G,
+        '\'' => SEGMENT_B,
+        '"' => SEGMENT_B | SEGMENT_F,
+        ' ' => BLANK,
+        _ => return None,
+    };
+
+    Some(segments)
+}
+
+/// Converts text of any length into one glyph per character, a `.` lights the decimal point of the character before it.
+/// Colons are skipped since only one digit can show them.
+pub fn segments(text: &str) -> Result<Vec<u8>, FormatError> {
+    let mut segments: Vec<u8> = vec![];
+
+    for char in text.chars() {
+        match char {
+            '.' => match segments.last_mut() {
+                Some(last) if *last & SEGMENT_DP == 0 => *last |= SEGMENT_DP,
+                _ => segments.push(SEGMENT_DP),
+            },
+            ':' => {}
+            char => segments.push(glyph(char).ok_or(FormatError::UnsupportedCharacter(char))?),
+        }
+    }
+
+    Ok(segments)
+}
+
+/// Converts text into the segments of each digit.
+/// A `.` lights the decimal point of the character before it and a `:` turns on the colon,
+/// neither of them takes a digit of their own.
+pub fn format(text: &str, align: Align) -> Result<Digits, FormatError> {
+    let segments = segments(text)?;
+    let colon = text.contains(':');
+
+    if segments.len() > DIGIT_COUNT {
+        return Err(FormatError::Overflow { required: segments.len() });
+    }
+
+    let mut digits = [BLANK; DIGIT_COUNT];
+
+    let offset = match align {
+        Align::Left => 0,
+        Align::Right => DIGIT_COUNT - segments.len(),
+    };
+
+    digits[offset..offset + segments.len()].copy_from_slice(&segments);
+
+    if colon {
+        digits[COLON_DIGIT] |= SEGMENT_DP;
+    }
+
+    Ok(digits)
+}
+
+/// Right aligns any value that can be displayed, such as a number.
+pub fn format_number<Value>(value: Value) -> Result<Digits, FormatError> where Value: Display {
+    format(&value.to_string(), Align::Right)
+}
+
+/// Right aligns a signed integer, from -999 up to 9999.
+pub fn format_integer(value: i32) -> Result<Digits, FormatError> {
+    format(&value.to_string(), Align::Right)
+}
+
+/// Right aligns a number rounded to the given amount of decimals, using the decimal point segment.
+pub fn format_fixed(value: f32, decimals: usize) -> Result<Digits, FormatError> {
+    format(&format!("{:.*}", decimals, value), Align::Right)
+}
+
+/// Shows a duration as `mm:ss` with the colon on, up to 99:59.
+pub fn format_minutes_seconds(total_seconds: u32) -> Result<Digits, FormatError> {
+    format_clock(total_seconds / 60, total_seconds % 60)
+}
+
+/// Shows a duration as `hh:mm` with the colon on, up to 99:59.
+pub fn format_hours_minutes(total_minutes: u32) -> Result<Digits, FormatError> {
+    format_clock(total_minutes / 60, total_minutes % 60)
+}
+
+/// Shows a temperature followed by the degree sign and the unit, the unit is dropped when there is no room for it.
+pub fn format_temperature(value: i32, unit: char) -> Result<Digits, FormatError> {
+    match format(&format!("{}°{}", value, unit), Align::Right) {
+        Err(FormatError::Overflow { .. }) => format(&format!("{}°", value), Align::Right),
+        result => result,
+    }
+}
+
+fn format_clock(high: u32, low: u32) -> Result<Digits, FormatError> {
+    if high > 99 {
+        return Err(FormatError::Overflow { required: high.to_string().len() + 2 });
+    }
+
+    format(&format!("{:02}:{:02}", high, low), Align::Right)
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::seven_segment::*;
+
+    const ONE: u8 = HEX_DIGITS[1];
+    const TWO: u8 = HEX_DIGITS[2];
+    const THREE: u8 = HEX_DIGITS[3];
+    const FOUR: u8 = HEX_DIGITS[4];
+    const FIVE: u8 = HEX_DIGITS[5];
+
+    #[test]
+    fn formats_negative_numbers() {
+        assert_eq!(format_integer(-12), Ok([BLANK, MINUS, ONE, TWO]));
+        assert_eq!(format_integer(-999), Ok([MINUS, HEX_DIGITS[9], HEX_DIGITS[9], HEX_DIGITS[9]]));
+        assert_eq!(format_integer(-1000), Err(FormatError::Overflow { required: 5 }));
+        assert_eq!(format_fixed(-1.5, 1), Ok([BLANK, MINUS, ONE | SEGMENT_DP, FIVE]));
+    }
+
+    #[test]
+    fn decimal_point_lights_the_previous_digit() {
+        assert_eq!(format_fixed(1.234, 2), Ok([BLANK, ONE | SEGMENT_DP, TWO, THREE]));
+        assert_eq!(format_number(12.5), Ok([BLANK, ONE, TWO | SEGMENT_DP, FIVE]));
+        assert_eq!(format("1.2", Align::Left), Ok([ONE | SEGMENT_DP, TWO, BLANK, BLANK]));
+
+        // Without a digit before it, or when it already has one, the point takes a digit of its own
+        assert_eq!(segments(".5"), Ok(vec![SEGMENT_DP, FIVE]));
+        assert_eq!(segments("1.."), Ok(vec![ONE | SEGMENT_DP, SEGMENT_DP]));
+    }
+
+    #[test]
+    fn colon_uses_the_second_digit() {
+        assert_eq!(format("12:34", Align::Right), Ok([ONE, TWO | SEGMENT_DP, THREE, FOUR]));
+        assert_eq!(format_minutes_seconds(125), Ok([HEX_DIGITS[0], TWO | SEGMENT_DP, HEX_DIGITS[0], FIVE]));
+        assert_eq!(format_hours_minutes(99 * 60 + 59), Ok([HEX_DIGITS[9], HEX_DIGITS[9] | SEGMENT_DP, FIVE, HEX_DIGITS[9]]));
+    }
+
+    #[test]
+    fn temperature_shows_the_degree_sign() {
+        assert_eq!(format_temperature(21, 'C'), Ok([TWO, ONE, DEGREE, HEX_DIGITS[12]]));
+        assert_eq!(format_temperature(5, 'F'), Ok([BLANK, FIVE, DEGREE, HEX_DIGITS[15]]));
+
+        // The unit is dropped when it doesn't fit
+        assert_eq!(format_temperature(-15, 'C'), Ok([MINUS, ONE, FIVE, DEGREE]));
+        assert_eq!(format_temperature(-150, 'C'), Err(FormatError::Overflow { required: 5 }));
+    }
+
+    #[test]
+    fn temperature_reports_unsupported_units() {
+        assert_eq!(format_temperature(21, 'X'), Err(FormatError::UnsupportedCharacter('X')));
+        assert_eq!(format_temperature(-150, 'X'), Err(FormatError::UnsupportedCharacter('X')));
+    }
+
+    #[test]
+    fn reports_what_does_not_fit() {
+        assert_eq!(format("hello", Align::Left), Err(FormatError::Overflow { required: 5 }));
+        assert_eq!(format_number(12345), Err(FormatError::Overflow { required: 5 }));
+        assert_eq!(format_minutes_seconds(100 * 60), Err(FormatError::Overflow { required: 5 }));
+        assert_eq!(format("W", Align::Left), Err(FormatError::UnsupportedCharacter('W')));
+    }
+}
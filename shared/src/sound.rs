@@ -0,0 +1,137 @@
+use std::sync::{Arc, mpsc, Mutex};
+use std::sync::mpsc::{Receiver, Sender};
+use std::thread::spawn;
+
+use anyhow::anyhow;
+use esp_idf_hal::delay::FreeRtos;
+use esp_idf_hal::gpio::OutputPin;
+use esp_idf_hal::peripheral::Peripheral;
+use esp_idf_hal::rmt::{FixedLengthSignal, PinState, Pulse, PulseTicks, RmtChannel, TxRmtDriver};
+use esp_idf_hal::rmt::config::{Loop, TransmitConfig};
+
+pub use crate::sound_effect::{Effect, MAX_PULSE_TICKS};
+use crate::sound_effect::Step;
+
+/// Plays short sound effects on a passive buzzer from a background thread, so callers never block.
+/// A new effect interrupts the one currently playing. If an effect fails to play, the error is
+/// returned by the next call to `play`.
+pub struct Sound {
+    sender: Sender<Effect>,
+    failure: Arc<Mutex<Option<anyhow::Error>>>,
+}
+
+impl Sound {
+    pub fn new(
+        channel: impl Peripheral<P=impl RmtChannel> + 'static,
+        pin: impl Peripheral<P=impl OutputPin> + 'static,
+    ) -> anyhow::Result<Sound> {
+        let config = TransmitConfig::new().looping(Loop::Endless);
+        let transmitter = TxRmtDriver::new(channel, pin, &config)?;
+
+        Ok(Self::from_driver(transmitter))
+    }
+
+    pub fn from_driver(transmitter: TxRmtDriver<'static>) -> Sound {
+        let (sender, receiver) = mpsc::channel();
+        let failure = Arc::new(Mutex::new(None));
+        let thread_failure = failure.clone();
+
+        spawn(move || {
+            let mut transmitter = transmitter;
+
+            while let Ok(effect) = receiver.recv() {
+                let mut next = Some(effect);
+
+                while let Some(effect) = next.take() {
+                    next = match play_effect(&mut transmitter, &receiver, effect) {
+                        Ok(next) => next,
+                        Err(error) => {
+                            transmitter.stop().ok();
+                            *thread_failure.lock().unwrap() = Some(error);
+                            None
+                        }
+                    };
+                }
+            }
+        });
+
+        Self { sender, failure }
+    }
+
+    pub fn play(&self, effect: Effect) -> anyhow::Result<()> {
+        self.sender
+            .send(effect)
+            .map_err(|error| anyhow!("sound thread is not running: {:?}", error))?;
+
+        match self.failure.lock().unwrap().take() {
+            Some(error) => Err(error.context("failed to play the previous sound effect")),
+            None => Ok(()),
+        }
+    }
+
+    pub fn beep(&self, frequency: u32, duration: u32) -> anyhow::Result<()> {
+        self.play(Effect::Beep { frequency, duration })
+    }
+
+    pub fn sweep(&self, from: u32, to: u32, duration: u32) -> anyhow::Result<()> {
+        self.play(Effect::Sweep { from, to, duration })
+    }
+
+    pub fn chirp(&self) -> anyhow::Result<()> {
+        self.play(Effect::Chirp)
+    }
+
+    pub fn success(&self) -> anyhow::Result<()> {
+        self.play(Effect::Success)
+    }
+
+    pub fn error(&self) -> anyhow::Result<()> {
+        self.play(Effect::Error)
+    }
+}
+
+// Plays every step of the effect, returning early with the next effect if one arrives in the meantime
+fn play_effect(transmitter: &mut TxRmtDriver<'static>, receiver: &Receiver<Effect>, effect: Effect) -> anyhow::Result<Option<Effect>> {
+    for step in effect.steps() {
+        if let Ok(next) = receiver.try_recv() {
+            transmitter.stop().ok();
+            return Ok(Some(next));
+        }
+
+        match step {
+            Step::Tone { frequency, duration } => play_tone(transmitter, frequency, duration)?,
+            Step::Silence { duration } => FreeRtos::delay_ms(duration),
+        }
+    }
+
+    Ok(None)
+}
+
+fn play_tone(transmitter: &mut TxRmtDriver<'static>, frequency: u32, duration: u32) -> anyhow::Result<()> {
+    if frequency == 0 {
+        FreeRtos::delay_ms(duration);
+        return Ok(());
+    }
+
+    let ticks_hz = transmitter.counter_clock()?;
+    let half = ticks_hz.0 / frequency / 2;
+
+    play_pulses(transmitter, half, half, duration)
+}
+
+/// Sounds a square wave made of a high and a low pulse, given in ticks, for the duration in milliseconds.
+/// Blocks until it is over. Pulses are clamped between 1 tick and `MAX_PULSE_TICKS`, so very low
+/// frequencies play as high as the RMT peripheral can go instead of failing.
+pub fn play_pulses(transmitter: &mut TxRmtDriver<'static>, high: u32, low: u32, duration: u32) -> anyhow::Result<()> {
+    let high = PulseTicks::new(high.clamp(1, MAX_PULSE_TICKS) as u16)?;
+    let low = PulseTicks::new(low.clamp(1, MAX_PULSE_TICKS) as u16)?;
+
+    let mut signal = FixedLengthSignal::<1>::new();
+    signal.set(0, &(Pulse::new(PinState::High, high), Pulse::new(PinState::Low, low)))?;
+
+    transmitter.start(signal)?;
+    FreeRtos::delay_ms(duration);
+    transmitter.stop()?;
+
+    Ok(())
+}
@@ -0,0 +1,106 @@
+// How long each frequency of a sweep is held for
+const SWEEP_STEP_MS: u32 = 5;
+// Longest pulse the RMT peripheral can hold, in ticks
+pub const MAX_PULSE_TICKS: u32 = 32_767;
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Effect {
+    Beep { frequency: u32, duration: u32 },
+    Sweep { from: u32, to: u32, duration: u32 },
+    Chirp,
+    Success,
+    Error,
+}
+
+/// A single tone or pause of an effect, durations are in milliseconds.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum Step {
+    Tone { frequency: u32, duration: u32 },
+    Silence { duration: u32 },
+}
+
+impl Effect {
+    pub fn steps(&self) -> Vec<Step> {
+        match *self {
+            Effect::Beep { frequency, duration } => vec![Step::Tone { frequency, duration }],
+            Effect::Sweep { from, to, duration } => {
+                let count = (duration / SWEEP_STEP_MS).max(1);
+
+                (0..count)
+                    .map(|step| {
+                        let frequency = from as i64 + (to as i64 - from as i64) * step as i64 / count as i64;
+                        Step::Tone { frequency: frequency as u32, duration: SWEEP_STEP_MS }
+                    })
+                    .collect()
+            }
+            Effect::Chirp => Effect::Sweep { from: 2000, to: 4000, duration: 40 }.steps(),
+            Effect::Success => vec![
+                Step::Tone { frequency: 1047, duration: 80 },
+                Step::Tone { frequency: 1319, duration: 80 },
+                Step::Tone { frequency: 1568, duration: 80 },
+                Step::Tone { frequency: 2093, duration: 160 },
+            ],
+            Effect::Error => vec![
+                Step::Tone { frequency: 392, duration: 150 },
+                Step::Silence { duration: 50 },
+                Step::Tone { frequency: 262, duration: 300 },
+            ],
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::sound_effect::{Effect, Step};
+
+    fn length(steps: &[Step]) -> u32 {
+        steps
+            .iter()
+            .map(|step| match *step {
+                Step::Tone { duration, .. } | Step::Silence { duration } => duration,
+            })
+            .sum()
+    }
+
+    #[test]
+    fn beep_is_a_single_tone() {
+        assert_eq!(Effect::Beep { frequency: 1500, duration: 30 }.steps(), [Step::Tone { frequency: 1500, duration: 30 }]);
+    }
+
+    #[test]
+    fn sweeps_rise_and_fall_in_even_steps() {
+        assert_eq!(Effect::Sweep { from: 1000, to: 2000, duration: 20 }.steps(), [
+            Step::Tone { frequency: 1000, duration: 5 },
+            Step::Tone { frequency: 1250, duration: 5 },
+            Step::Tone { frequency: 1500, duration: 5 },
+            Step::Tone { frequency: 1750, duration: 5 },
+        ]);
+
+        assert_eq!(Effect::Sweep { from: 2000, to: 1000, duration: 10 }.steps(), [
+            Step::Tone { frequency: 2000, duration: 5 },
+            Step::Tone { frequency: 1500, duration: 5 },
+        ]);
+    }
+
+    #[test]
+    fn short_sweeps_hold_the_first_frequency() {
+        assert_eq!(Effect::Sweep { from: 1000, to: 2000, duration: 2 }.steps(), [Step::Tone { frequency: 1000, duration: 5 }]);
+    }
+
+    #[test]
+    fn chirp_is_a_quick_rising_sweep() {
+        let steps = Effect::Chirp.steps();
+
+        assert_eq!(steps, Effect::Sweep { from: 2000, to: 4000, duration: 40 }.steps());
+        assert_eq!(length(&steps), 40);
+    }
+
+    #[test]
+    fn success_and_error_have_a_fixed_length() {
+        assert_eq!(Effect::Success.steps().len(), 4);
+        assert_eq!(length(&Effect::Success.steps()), 400);
+
+        assert_eq!(Effect::Error.steps()[1], Step::Silence { duration: 50 });
+        assert_eq!(length(&Effect::Error.steps()), 500);
+    }
+}